@@ -0,0 +1,15 @@
+use std::fs;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use crate::error::{AppError, Result};
+
+use super::extract::write_entry;
+
+/// Decompress a gzip-compressed single file into `dest_file` as an executable.
+pub(crate) fn extract_gz_executable(archive_path: &Path, dest_file: &Path) -> Result<()> {
+    let file = fs::File::open(archive_path).map_err(|e| AppError::io(e.to_string()))?;
+    let mut decoder = GzDecoder::new(file);
+    write_entry(dest_file, false, &mut decoder, Some(0o755), None)
+}
@@ -56,6 +56,20 @@ fn create_symlink(
     ))
 }
 
+/// Recreate the symlink at `link_path` as `dest`, pointing at the same target.
+pub(crate) fn copy_symlink(link_path: &Path, dest: &Path) -> Result<()> {
+    let target = fs::read_link(link_path)
+        .map_err(|e| AppError::io(format!("failed to read symlink {link_path:?}: {e}")))?;
+    let target_kind = fs::metadata(link_path).ok().map(|metadata| {
+        if metadata.is_dir() {
+            SymlinkTargetKind::Dir
+        } else {
+            SymlinkTargetKind::File
+        }
+    });
+    create_symlink(&target, dest, target_kind)
+}
+
 fn create_hard_link(target: &Path, link_path: &Path) -> Result<()> {
     if let Err(e) = fs::hard_link(target, link_path) {
         log::warn!("hard_link failed ({e}), falling back to copy");
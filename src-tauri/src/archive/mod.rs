@@ -1,11 +1,16 @@
 //! Shared archive extraction utilities.
 
 mod extract;
+mod gz;
 mod links;
 mod path;
 mod tar_gz;
 mod zip_ops;
 
+pub(crate) use gz::extract_gz_executable;
+pub(crate) use links::copy_symlink;
 pub(crate) use path::parse_entry_rel_path;
 pub(crate) use tar_gz::{extract_tar_gz_flat, extract_tar_gz_mapped};
-pub(crate) use zip_ops::{append_dir_tree_to_zip, extract_zip_flat, extract_zip_mapped};
+pub(crate) use zip_ops::{
+    append_dir_tree_to_zip, extract_zip_flat, extract_zip_mapped, read_zip_root_file,
+};
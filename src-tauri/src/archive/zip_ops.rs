@@ -118,3 +118,28 @@ pub(crate) fn extract_zip_flat(archive_path: &Path, dest_dir: &Path) -> Result<(
         Some(dest_dir.join(stripped))
     })
 }
+
+/// Read a UTF-8 file at the archive root, looking inside the common top-level directory.
+///
+/// Returns `Ok(None)` if the file is not present.
+pub(crate) fn read_zip_root_file(archive_path: &Path, name: &str) -> Result<Option<String>> {
+    let file = fs::File::open(archive_path).map_err(|e| AppError::io(e.to_string()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| AppError::io(e.to_string()))?;
+
+    let entry_name = match detect_common_top_dir(archive.file_names()) {
+        Some(top) => format!("{top}/{name}"),
+        None => name.to_string(),
+    };
+
+    let mut entry = match archive.by_name(&entry_name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(AppError::io(e.to_string())),
+    };
+
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| AppError::io(e.to_string()))?;
+    Ok(Some(content))
+}
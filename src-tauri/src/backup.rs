@@ -10,8 +10,11 @@ use crate::archive::{
     append_dir_tree_to_zip, extract_tar_gz_mapped, extract_zip_mapped, parse_entry_rel_path,
 };
 use crate::config::{load_config, with_config_mut, BackupInfo, BackupMetadata, InstanceConfig};
+use crate::disk_usage::disk_size;
 use crate::error::{AppError, Result};
-use crate::paths::{get_backups_dir, get_instance_core_dir, get_instance_dir};
+use crate::instance::{instance_data_dir, instance_root_dir};
+use crate::paths::{get_backups_dir, get_instance_dir};
+use crate::preflight::ensure_free_space;
 use crate::validation::{resolve_backup_path, validate_instance_id};
 
 /// Check if a backup path is in tar.gz format.
@@ -32,8 +35,9 @@ fn create_backup_archive(
     fs::create_dir_all(&backups_dir)
         .map_err(|e| AppError::backup(format!("Failed to create backups dir: {}", e)))?;
 
-    let core_dir = get_instance_core_dir(instance_id);
-    let data_dir = core_dir.join("data");
+    let data_dir = instance_data_dir(instance_id);
+    // Compression only makes the archive smaller than the data.
+    ensure_free_space(&backups_dir, disk_size(&data_dir))?;
 
     // Generate backup filename
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
@@ -98,7 +102,7 @@ pub fn create_backup(instance_id: &str, auto_generated: bool) -> Result<String>
         .get(instance_id)
         .ok_or_else(|| AppError::instance_not_found(instance_id))?;
 
-    let data_dir = get_instance_core_dir(instance_id).join("data");
+    let data_dir = instance_data_dir(instance_id);
     if !data_dir.exists() {
         return Err(AppError::backup("No data directory to back up"));
     }
@@ -214,19 +218,31 @@ fn read_backup_metadata_zip(backup_path: &Path) -> Result<BackupMetadata> {
         .map_err(|e| AppError::backup(format!("Failed to parse metadata: {}", e)))
 }
 
-/// Restore a backup to its original instance
-pub fn restore_backup(backup_path: &str) -> Result<()> {
+/// Instance a backup belongs to, read from its metadata.
+pub fn backup_instance_id(backup_path: &str) -> Result<String> {
+    let backup_path = resolve_backup_path(backup_path, true)?;
+    Ok(read_backup_metadata(&backup_path)?.instance_id)
+}
+
+/// Restore a backup to its original instance, returning the instance id.
+pub fn restore_backup(backup_path: &str) -> Result<String> {
     let backup_path = resolve_backup_path(backup_path, true)?;
 
     // Read metadata
     let metadata = read_backup_metadata(&backup_path)?;
 
-    // Check if version is installed
+    // Check if version is installed; dev-mode instances run from their
+    // checkout and have no version.
     let config = load_config()?;
-    if !config
-        .installed_versions
-        .iter()
-        .any(|v| v.version == metadata.version)
+    let is_dev = config
+        .instances
+        .get(&metadata.instance_id)
+        .is_some_and(|instance| instance.source_dir.is_some());
+    if !is_dev
+        && !config
+            .installed_versions
+            .iter()
+            .any(|v| v.version == metadata.version)
     {
         return Err(AppError::version_not_found(&metadata.version));
     }
@@ -238,24 +254,26 @@ pub fn restore_backup(backup_path: &str) -> Result<()> {
     }
 
     let instance_dir = get_instance_dir(instance_id);
-    let core_dir = get_instance_core_dir(instance_id);
+    let root_dir = instance_root_dir(instance_id);
 
     // Extract backup to existing instance
-    extract_backup_to_instance(&backup_path, &instance_dir, &core_dir)?;
+    extract_backup_to_instance(&backup_path, &instance_dir, &root_dir)?;
 
     // Update instance version if different
-    with_config_mut(|config| {
-        if let Some(instance) = config.instances.get_mut(instance_id) {
-            instance.version = metadata.version.clone();
-        }
-        Ok(())
-    })?;
+    if !is_dev {
+        with_config_mut(|config| {
+            if let Some(instance) = config.instances.get_mut(instance_id) {
+                instance.version = metadata.version.clone();
+            }
+            Ok(())
+        })?;
+    }
 
-    Ok(())
+    Ok(metadata.instance_id)
 }
 
 /// Route an archive entry to the correct destination directory.
-fn route_backup_entry(relative: &Path, instance_dir: &Path, core_dir: &Path) -> Option<PathBuf> {
+fn route_backup_entry(relative: &Path, instance_dir: &Path, root_dir: &Path) -> Option<PathBuf> {
     // Skip backup.toml
     if relative == Path::new("backup.toml") {
         return None;
@@ -266,9 +284,9 @@ fn route_backup_entry(relative: &Path, instance_dir: &Path, core_dir: &Path) ->
         return None;
     }
 
-    // Route data/ entries to core_dir, everything else to instance_dir
+    // Route data/ entries to the AstrBot root, everything else to instance_dir
     if relative.starts_with("data") {
-        Some(core_dir.join(relative))
+        Some(root_dir.join(relative))
     } else {
         Some(instance_dir.join(relative))
     }
@@ -278,11 +296,11 @@ fn route_backup_entry(relative: &Path, instance_dir: &Path, core_dir: &Path) ->
 fn extract_backup_to_instance(
     backup_path: &Path,
     instance_dir: &Path,
-    core_dir: &Path,
+    root_dir: &Path,
 ) -> Result<()> {
     let routing = |raw_path: &str| -> Option<PathBuf> {
         let relative = parse_entry_rel_path(raw_path)?;
-        route_backup_entry(&relative, instance_dir, core_dir)
+        route_backup_entry(&relative, instance_dir, root_dir)
     };
 
     if is_tar_gz(backup_path) {
@@ -308,7 +326,7 @@ pub fn restore_data_to_instance(backup_path: &str, instance_id: &str) -> Result<
     validate_instance_id(instance_id)?;
 
     let backup_path = resolve_backup_path(backup_path, true)?;
-    let core_dir = get_instance_core_dir(instance_id);
+    let root_dir = instance_root_dir(instance_id);
 
     let routing = |raw_path: &str| -> Option<PathBuf> {
         let relative = parse_entry_rel_path(raw_path)?;
@@ -316,13 +334,13 @@ pub fn restore_data_to_instance(backup_path: &str, instance_id: &str) -> Result<
             return None;
         }
 
-        Some(core_dir.join(&relative))
+        Some(root_dir.join(&relative))
     };
 
     if is_tar_gz(&backup_path) {
-        extract_tar_gz_mapped(&backup_path, &core_dir, routing)
+        extract_tar_gz_mapped(&backup_path, &root_dir, routing)
     } else {
-        extract_zip_mapped(&backup_path, &core_dir, routing)
+        extract_zip_mapped(&backup_path, &root_dir, routing)
     }
     .map_err(|e| AppError::backup(format!("Failed to restore backup data: {}", e)))
 }
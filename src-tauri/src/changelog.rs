@@ -0,0 +1,252 @@
+//! Release notes aggregated across a version range.
+//!
+//! Notes are split into sections on their Markdown headings so the UI can
+//! show them grouped, and sections announcing breaking changes are flagged.
+
+use serde::Serialize;
+
+use crate::error::{AppError, Result};
+use crate::github::{parse_release_version, GitHubRelease};
+
+/// Heading words that announce a breaking change, matched case-insensitively.
+const BREAKING_KEYWORDS: &[&str] = &["breaking", "不兼容", "破坏性", "重大变更"];
+
+/// Changes from one version to another.
+#[derive(Debug, Clone, Serialize)]
+pub struct Changelog {
+    pub from: String,
+    pub to: String,
+    /// Releases after `from` up to and including `to`, oldest first.
+    pub releases: Vec<ReleaseNotes>,
+    pub has_breaking_changes: bool,
+    /// False when the fetched release list does not reach back to `from`,
+    /// so older notes are missing.
+    pub complete: bool,
+}
+
+/// Notes of a single release.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseNotes {
+    pub tag_name: String,
+    pub name: String,
+    pub published_at: String,
+    pub prerelease: bool,
+    pub html_url: String,
+    pub sections: Vec<ChangelogSection>,
+    pub breaking: bool,
+}
+
+/// Part of the notes under one heading.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogSection {
+    /// Heading text without the `#` markers; empty for text before the first heading.
+    pub heading: String,
+    pub body: String,
+    pub breaking: bool,
+}
+
+/// Collect the notes of every release between `from` and `to`.
+///
+/// Prereleases are skipped unless `include_prereleases` is set, except `to`
+/// itself. A downgrade lists the releases being rolled back.
+pub fn collect_changelog(
+    releases: &[GitHubRelease],
+    from: &str,
+    to: &str,
+    include_prereleases: bool,
+) -> Result<Changelog> {
+    let from_version = parse_release_version(from)
+        .ok_or_else(|| AppError::config(format!("Not a release version: {}", from)))?;
+    let to_version = parse_release_version(to)
+        .ok_or_else(|| AppError::config(format!("Not a release version: {}", to)))?;
+    let (low, high) = if from_version <= to_version {
+        (&from_version, &to_version)
+    } else {
+        (&to_version, &from_version)
+    };
+
+    let mut in_range: Vec<(semver::Version, &GitHubRelease)> = releases
+        .iter()
+        .filter_map(|release| parse_release_version(&release.tag_name).map(|v| (v, release)))
+        .filter(|(v, _)| v > low && v <= high)
+        .filter(|(v, release)| include_prereleases || !release.prerelease || *v == to_version)
+        .collect();
+    in_range.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let complete = releases
+        .iter()
+        .filter_map(|release| parse_release_version(&release.tag_name))
+        .any(|v| v <= *low);
+
+    let releases: Vec<ReleaseNotes> = in_range
+        .into_iter()
+        .map(|(_, release)| {
+            let sections = split_sections(release.body.as_deref().unwrap_or_default());
+            ReleaseNotes {
+                tag_name: release.tag_name.clone(),
+                name: release.name.clone(),
+                published_at: release.published_at.clone(),
+                prerelease: release.prerelease,
+                html_url: release.html_url.clone(),
+                breaking: sections.iter().any(|s| s.breaking),
+                sections,
+            }
+        })
+        .collect();
+
+    Ok(Changelog {
+        from: from.to_string(),
+        to: to.to_string(),
+        has_breaking_changes: releases.iter().any(|r| r.breaking),
+        releases,
+        complete,
+    })
+}
+
+/// Split Markdown release notes on their headings.
+fn split_sections(body: &str) -> Vec<ChangelogSection> {
+    let mut sections = Vec::new();
+    let mut heading = String::new();
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in body.lines() {
+        if let Some(text) = heading_text(line) {
+            push_section(&mut sections, &heading, &lines);
+            heading = text.to_string();
+            lines.clear();
+        } else {
+            lines.push(line);
+        }
+    }
+    push_section(&mut sections, &heading, &lines);
+    sections
+}
+
+fn push_section(sections: &mut Vec<ChangelogSection>, heading: &str, lines: &[&str]) {
+    let body = lines.join("\n").trim().to_string();
+    if heading.is_empty() && body.is_empty() {
+        return;
+    }
+    sections.push(ChangelogSection {
+        heading: heading.to_string(),
+        body,
+        breaking: is_breaking(heading),
+    });
+}
+
+/// Text of an ATX heading line (`## Title`), if `line` is one.
+fn heading_text(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim())
+}
+
+fn is_breaking(heading: &str) -> bool {
+    let heading = heading.to_lowercase();
+    BREAKING_KEYWORDS
+        .iter()
+        .any(|keyword| heading.contains(keyword))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool, body: &str) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            name: tag.to_string(),
+            published_at: String::new(),
+            prerelease,
+            assets: Vec::new(),
+            html_url: String::new(),
+            body: Some(body.to_string()),
+        }
+    }
+
+    fn tags(changelog: &Changelog) -> Vec<&str> {
+        changelog
+            .releases
+            .iter()
+            .map(|release| release.tag_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn heading_text_accepts_atx_headings() {
+        assert_eq!(heading_text("# Title"), Some("Title"));
+        assert_eq!(heading_text("  ### Fixes ###"), Some("Fixes"));
+        assert_eq!(heading_text("##"), Some(""));
+        assert_eq!(heading_text("#hashtag"), None);
+        assert_eq!(heading_text("####### Too deep"), None);
+        assert_eq!(heading_text("Plain text"), None);
+    }
+
+    #[test]
+    fn split_sections_groups_lines_under_headings() {
+        let sections = split_sections("Intro\n\n## Features\n- a\n- b\n## Breaking Changes\n- c\n");
+        let headings: Vec<&str> = sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, ["", "Features", "Breaking Changes"]);
+        assert_eq!(sections[0].body, "Intro");
+        assert_eq!(sections[1].body, "- a\n- b");
+        assert!(!sections[1].breaking);
+        assert!(sections[2].breaking);
+    }
+
+    #[test]
+    fn split_sections_skips_empty_preamble() {
+        let sections = split_sections("\n## 不兼容更新\n- x");
+        assert_eq!(sections.len(), 1);
+        assert!(sections[0].breaking);
+        assert!(split_sections("").is_empty());
+    }
+
+    #[test]
+    fn collect_changelog_lists_upgrade_range_oldest_first() {
+        let releases = [
+            release("v4.3.0", false, "## Breaking\n- x"),
+            release("v4.2.0-rc.1", true, ""),
+            release("v4.1.0", false, ""),
+            release("v4.0.0", false, ""),
+        ];
+        let changelog = collect_changelog(&releases, "v4.0.0", "v4.3.0", false).unwrap();
+        assert_eq!(tags(&changelog), ["v4.1.0", "v4.3.0"]);
+        assert!(changelog.has_breaking_changes);
+        assert!(changelog.complete);
+
+        let with_pre = collect_changelog(&releases, "v4.0.0", "v4.3.0", true).unwrap();
+        assert_eq!(tags(&with_pre), ["v4.1.0", "v4.2.0-rc.1", "v4.3.0"]);
+    }
+
+    #[test]
+    fn collect_changelog_keeps_prerelease_target_and_downgrades() {
+        let releases = [
+            release("v4.2.0-rc.1", true, ""),
+            release("v4.1.0", false, ""),
+            release("v4.0.0", false, ""),
+        ];
+        let to_pre = collect_changelog(&releases, "v4.1.0", "v4.2.0-rc.1", false).unwrap();
+        assert_eq!(tags(&to_pre), ["v4.2.0-rc.1"]);
+
+        let downgrade = collect_changelog(&releases, "v4.1.0", "v4.0.0", false).unwrap();
+        assert_eq!(tags(&downgrade), ["v4.1.0"]);
+        assert!(!downgrade.has_breaking_changes);
+    }
+
+    #[test]
+    fn collect_changelog_flags_missing_history() {
+        let releases = [release("v4.3.0", false, ""), release("v4.2.0", false, "")];
+        let changelog = collect_changelog(&releases, "v4.0.0", "v4.3.0", false).unwrap();
+        assert_eq!(tags(&changelog), ["v4.2.0", "v4.3.0"]);
+        assert!(!changelog.complete);
+        assert!(collect_changelog(&releases, "dev", "v4.3.0", false).is_err());
+    }
+}
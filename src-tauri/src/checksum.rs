@@ -0,0 +1,56 @@
+//! SHA-256 helpers for files and archives.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use sha2::{Digest as _, Sha256};
+
+use crate::error::{AppError, Result};
+
+/// Compute the lowercase hex SHA-256 digest of a file.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)
+        .map_err(|e| AppError::io(format!("Failed to open {:?} for hashing: {}", path, e)))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .map_err(|e| AppError::io(format!("Failed to hash {:?}: {}", path, e)))?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Hash `path` and fail with `ChecksumMismatch` unless it matches `expected`.
+///
+/// Returns the computed digest.
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<String> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(actual)
+    } else {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Err(AppError::checksum_mismatch(&file, expected, &actual))
+    }
+}
+
+/// Look up the digest of `file_name` in a `SHA256SUMS` style listing.
+///
+/// Lines are `<hex digest>  <name>`, optionally with a `*` binary marker.
+pub fn find_in_sha256sums(sums: &str, file_name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (digest, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start().trim_start_matches('*');
+        (name == file_name).then(|| digest.to_ascii_lowercase())
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut out, b| {
+            let _ = write!(out, "{:02x}", b);
+            out
+        })
+}
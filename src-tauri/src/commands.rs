@@ -1,19 +1,28 @@
 use std::cmp::Ordering;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use reqwest::Client;
 use tauri::{AppHandle, State};
 
 use crate::backup;
+use crate::changelog::{self, Changelog};
 use crate::config::{
     load_config, reload_config, with_config_mut, AppConfig, BackupInfo, InstalledVersion,
+    ProxyConfig, RefKind, RetentionPolicy, UpdateChannel,
 };
+use crate::data_root::{self, DataRoot};
+use crate::disk_usage::{self, DiskUsage};
 use crate::download;
 use crate::error::{AppError, Result};
 use crate::github::{self, GitHubRelease};
 use crate::instance::{self, InstanceStatus, ProcessManager};
-use crate::paths;
+use crate::kit;
+use crate::mirror;
 use crate::platform;
+use crate::proxy;
+use crate::retention::{self, GcPlan, GcReport};
 use crate::component;
 use crate::component::ComponentsSnapshot;
 
@@ -31,24 +40,55 @@ fn sort_installed_versions_semver(versions: &mut [InstalledVersion]) {
     });
 }
 
+/// Timeout of the shared HTTP client.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub struct AppState {
-    pub client: Client,
+    client: RwLock<Client>,
     pub process_manager: Arc<ProcessManager>,
 }
 
+impl AppState {
+    /// Create the state with an HTTP client using the configured proxy.
+    ///
+    /// Falls back to the system proxy settings if the configured proxy is invalid.
+    pub fn new(process_manager: Arc<ProcessManager>) -> Result<Self> {
+        let proxy_config = load_config()
+            .map(|config| config.proxy.clone())
+            .unwrap_or_default();
+        let client = proxy::build_http_client(HTTP_TIMEOUT, &proxy_config).or_else(|e| {
+            log::warn!("Ignoring proxy settings: {}", e);
+            proxy::build_http_client(HTTP_TIMEOUT, &ProxyConfig::default())
+        })?;
+
+        Ok(Self {
+            client: RwLock::new(client),
+            process_manager,
+        })
+    }
+
+    /// The shared HTTP client. Cheap to clone, and replaced when the proxy changes.
+    pub fn client(&self) -> Client {
+        self.client
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
 pub async fn build_app_snapshot(process_manager: &ProcessManager) -> Result<AppSnapshot> {
     let config = load_config()?;
     let instances = instance::list_instances(process_manager).await?;
     let backups = backup::list_backups()?;
-    let mut config_for_snapshot = (*config).clone();
-    sort_installed_versions_semver(&mut config_for_snapshot.installed_versions);
+    let config_for_snapshot = ConfigSnapshot::new(&config);
 
     Ok(AppSnapshot {
         instances,
-        versions: config_for_snapshot.installed_versions.clone(),
+        versions: config_for_snapshot.config.installed_versions.clone(),
         backups,
         components: component::build_components_snapshot(),
         config: config_for_snapshot,
+        disk_usage: disk_usage::disk_usage(),
     })
 }
 
@@ -56,15 +96,15 @@ pub async fn build_app_snapshot_from_disk(process_manager: &ProcessManager) -> R
     let config = reload_config()?;
     let instances = instance::list_instances(process_manager).await?;
     let backups = backup::list_backups()?;
-    let mut config_for_snapshot = (*config).clone();
-    sort_installed_versions_semver(&mut config_for_snapshot.installed_versions);
+    let config_for_snapshot = ConfigSnapshot::new(&config);
 
     Ok(AppSnapshot {
         instances,
-        versions: config_for_snapshot.installed_versions.clone(),
+        versions: config_for_snapshot.config.installed_versions.clone(),
         backups,
         components: component::build_components_snapshot(),
         config: config_for_snapshot,
+        disk_usage: disk_usage::disk_usage(),
     })
 }
 
@@ -84,7 +124,32 @@ pub struct AppSnapshot {
     pub versions: Vec<InstalledVersion>,
     pub backups: Vec<BackupInfo>,
     pub components: ComponentsSnapshot,
+    pub config: ConfigSnapshot,
+    pub disk_usage: DiskUsage,
+}
+
+/// Config as sent to the webview, which only learns whether secrets are set.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConfigSnapshot {
+    #[serde(flatten)]
     pub config: AppConfig,
+    pub has_github_token: bool,
+    pub has_proxy_password: bool,
+}
+
+impl ConfigSnapshot {
+    fn new(config: &AppConfig) -> Self {
+        let mut config = config.clone();
+        sort_installed_versions_semver(&mut config.installed_versions);
+        // Emptied secrets are left out by `skip_serializing_if`.
+        let has_github_token = !std::mem::take(&mut config.github_token).is_empty();
+        let has_proxy_password = !std::mem::take(&mut config.proxy.password).is_empty();
+        Self {
+            config,
+            has_github_token,
+            has_proxy_password,
+        }
+    }
 }
 
 // === Config ===
@@ -95,34 +160,93 @@ pub fn is_macos() -> bool {
 }
 
 #[tauri::command]
-pub async fn save_github_proxy(github_proxy: String, state: State<'_, AppState>) -> Result<()> {
-    // Test connectivity first
-    let url = github::build_api_url(&github_proxy);
-    download::check_url(&state.client, &url).await?;
-    // Test passed, save
+pub async fn save_github_proxies(github_proxies: Vec<String>) -> Result<()> {
+    let github_proxies = mirror::normalize_mirrors(&github_proxies);
+    mirror::validate_mirrors(&github_proxies)?;
     with_config_mut(move |config| {
-        config.github_proxy = github_proxy;
+        config.github_proxies = github_proxies;
         Ok(())
     })
 }
 
 #[tauri::command]
-pub async fn save_pypi_mirror(pypi_mirror: String, state: State<'_, AppState>) -> Result<()> {
-    // Test connectivity first
-    let base = if pypi_mirror.is_empty() {
-        "https://pypi.org".to_string()
-    } else {
-        pypi_mirror.trim_end_matches('/').to_string()
-    };
-    let url = format!("{}/simple/", base);
-    download::check_url(&state.client, &url).await?;
-    // Test passed, save
+pub async fn save_github_token(github_token: String) -> Result<()> {
+    let github_token = github_token.trim().to_string();
+    with_config_mut(move |config| {
+        config.github_token = github_token;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn save_source_repositories(source_repositories: Vec<String>) -> Result<()> {
+    let mut repos: Vec<String> = Vec::new();
+    for repo in source_repositories {
+        let repo = repo.trim().trim_matches('/').to_string();
+        if repo.is_empty() || repos.contains(&repo) {
+            continue;
+        }
+        github::validate_repo(&repo)?;
+        repos.push(repo);
+    }
+    with_config_mut(move |config| {
+        config.source_repositories = repos;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn save_pypi_mirrors(pypi_mirrors: Vec<String>) -> Result<()> {
+    let pypi_mirrors = mirror::normalize_mirrors(&pypi_mirrors);
+    mirror::validate_mirrors(&pypi_mirrors)?;
     with_config_mut(move |config| {
-        config.pypi_mirror = pypi_mirror;
+        config.pypi_mirrors = pypi_mirrors;
         Ok(())
     })
 }
 
+#[tauri::command]
+pub async fn probe_mirrors(
+    state: State<'_, AppState>,
+    kind: mirror::MirrorKind,
+    mirrors: Vec<String>,
+) -> Result<Vec<mirror::MirrorProbe>> {
+    Ok(mirror::probe_mirrors(&state.client(), kind, &mirrors).await)
+}
+
+/// Save the proxy settings. The webview never sees the saved password, so
+/// `password` is `None` to keep it.
+#[tauri::command]
+pub async fn save_proxy_config(
+    state: State<'_, AppState>,
+    proxy: ProxyConfig,
+    password: Option<String>,
+) -> Result<()> {
+    let password = match password {
+        Some(password) => password,
+        None => load_config()?.proxy.password.clone(),
+    };
+    let proxy_config = ProxyConfig {
+        url: proxy.url.trim().to_string(),
+        username: proxy.username.trim().to_string(),
+        password,
+        no_proxy: proxy
+            .no_proxy
+            .iter()
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect(),
+    };
+    // Building the client validates the settings before they are saved.
+    let client = proxy::build_http_client(HTTP_TIMEOUT, &proxy_config)?;
+    with_config_mut(move |config| {
+        config.proxy = proxy_config;
+        Ok(())
+    })?;
+    *state.client.write().unwrap_or_else(|e| e.into_inner()) = client;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn save_close_to_tray(close_to_tray: bool) -> Result<()> {
     with_config_mut(move |config| {
@@ -150,6 +274,20 @@ pub async fn save_check_instance_update(check_instance_update: bool) -> Result<(
     })
 }
 
+#[tauri::command]
+pub async fn save_auto_update_hour(auto_update_hour: u8) -> Result<()> {
+    if auto_update_hour >= 24 {
+        return Err(AppError::config(format!(
+            "Invalid auto-update hour: {}",
+            auto_update_hour
+        )));
+    }
+    with_config_mut(move |config| {
+        config.auto_update_hour = auto_update_hour;
+        Ok(())
+    })
+}
+
 #[tauri::command]
 pub async fn save_persist_instance_state(persist_instance_state: bool) -> Result<()> {
     with_config_mut(move |config| {
@@ -162,7 +300,7 @@ pub async fn save_persist_instance_state(persist_instance_state: bool) -> Result
 
 #[tauri::command]
 pub fn is_instance_deployed(instance_id: &str) -> bool {
-    paths::is_instance_deployed(instance_id)
+    instance::is_instance_deployed(instance_id)
 }
 
 #[tauri::command]
@@ -172,7 +310,7 @@ pub async fn install_component(
 ) -> Result<String> {
     let id = component::ComponentId::from_str_id(&component_id)
         .ok_or_else(|| AppError::python(format!("Unknown component: {}", component_id)))?;
-    component::install_component(&state.client, id).await
+    component::install_component(&state.client(), id).await
 }
 
 #[tauri::command]
@@ -182,21 +320,170 @@ pub async fn reinstall_component(
 ) -> Result<String> {
     let id = component::ComponentId::from_str_id(&component_id)
         .ok_or_else(|| AppError::python(format!("Unknown component: {}", component_id)))?;
-    component::reinstall_component(&state.client, id).await
+    component::reinstall_component(&state.client(), id).await
+}
+
+#[tauri::command]
+pub async fn install_component_from_file(
+    state: State<'_, AppState>,
+    component_id: String,
+    path: String,
+) -> Result<String> {
+    let id = component::ComponentId::from_str_id(&component_id)
+        .ok_or_else(|| AppError::python(format!("Unknown component: {}", component_id)))?;
+
+    for instance_id in component::instances_using_component(id)? {
+        if state.process_manager.is_running(&instance_id).await {
+            return Err(AppError::instance_running());
+        }
+    }
+
+    component::install_component_from_file(id, Path::new(&path)).await
+}
+
+#[tauri::command]
+pub async fn check_component_updates(
+    state: State<'_, AppState>,
+) -> Result<Vec<component::ComponentUpdate>> {
+    component::check_component_updates(&state.client()).await
+}
+
+#[tauri::command]
+pub async fn update_component(state: State<'_, AppState>, component_id: String) -> Result<String> {
+    let id = component::ComponentId::from_str_id(&component_id)
+        .ok_or_else(|| AppError::python(format!("Unknown component: {}", component_id)))?;
+
+    // A running interpreter cannot be swapped out from under its instance.
+    for instance_id in component::instances_using_component(id)? {
+        if state.process_manager.is_running(&instance_id).await {
+            return Err(AppError::instance_running());
+        }
+    }
+
+    component::update_component(&state.client(), id).await
+}
+
+#[tauri::command]
+pub async fn discover_interpreters() -> Vec<component::DiscoveredInterpreter> {
+    component::discover_interpreters().await
+}
+
+#[tauri::command]
+pub async fn register_component_provider(
+    component_id: String,
+    path: String,
+) -> Result<component::DiscoveredInterpreter> {
+    let id = component::ComponentId::from_str_id(&component_id)
+        .ok_or_else(|| AppError::python(format!("Unknown component: {}", component_id)))?;
+    component::register_component_provider(id, Path::new(&path)).await
+}
+
+#[tauri::command]
+pub async fn unregister_component_provider(component_id: String) -> Result<()> {
+    let id = component::ComponentId::from_str_id(&component_id)
+        .ok_or_else(|| AppError::python(format!("Unknown component: {}", component_id)))?;
+    component::unregister_component_provider(id)
 }
 
 // === GitHub ===
 
 #[tauri::command]
 pub async fn fetch_releases(state: State<'_, AppState>) -> Result<Vec<GitHubRelease>> {
-    github::fetch_releases(&state.client).await
+    github::fetch_releases(&state.client()).await
+}
+
+/// Release notes of everything between an instance's version and `target`.
+///
+/// Prereleases are included when the instance follows the prerelease channel,
+/// unless `include_prereleases` says otherwise.
+#[tauri::command]
+pub async fn get_instance_changelog(
+    state: State<'_, AppState>,
+    instance_id: String,
+    target: String,
+    include_prereleases: Option<bool>,
+) -> Result<Changelog> {
+    let config = load_config()?;
+    let instance = config
+        .instances
+        .get(&instance_id)
+        .ok_or_else(|| AppError::instance_not_found(&instance_id))?;
+    if instance.source_dir.is_some() {
+        return Err(AppError::dev_instance_unversioned());
+    }
+    let include_prereleases =
+        include_prereleases.unwrap_or(instance.update_channel == UpdateChannel::Prerelease);
+
+    let releases = github::fetch_releases(&state.client()).await?;
+    changelog::collect_changelog(&releases, &instance.version, &target, include_prereleases)
 }
 
 // === Version Management ===
 
 #[tauri::command]
 pub async fn install_version(state: State<'_, AppState>, release: GitHubRelease) -> Result<()> {
-    download::download_version(&state.client, &release).await
+    download::download_version(&state.client(), &release).await
+}
+
+#[tauri::command]
+pub async fn install_version_from_ref(
+    state: State<'_, AppState>,
+    repo: String,
+    kind: RefKind,
+    ref_name: String,
+    version: Option<String>,
+) -> Result<String> {
+    download::install_version_from_ref(&state.client(), &repo, kind, &ref_name, version.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn update_ref_version(state: State<'_, AppState>, version: String) -> Result<String> {
+    // Instances on the version re-extract it, which cannot happen while running.
+    let config = load_config()?;
+    for (instance_id, instance) in &config.instances {
+        if instance.version == version && state.process_manager.is_running(instance_id).await {
+            return Err(AppError::instance_running());
+        }
+    }
+
+    download::update_ref_version(&state.client(), &version).await
+}
+
+#[tauri::command]
+pub async fn import_version(path: String, version: Option<String>) -> Result<String> {
+    // Validating and copying a large archive can take a while.
+    tauri::async_runtime::spawn_blocking(move || {
+        download::import_version(Path::new(&path), version.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::other(format!("Version import failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn export_offline_kit(versions: Vec<String>, path: String) -> Result<String> {
+    kit::export_offline_kit(&versions, Path::new(&path)).await
+}
+
+#[tauri::command]
+pub async fn import_offline_kit(state: State<'_, AppState>, path: String) -> Result<String> {
+    // Components in the kit replace installed ones, which must not be in use.
+    let manifest = kit::read_kit_manifest(Path::new(&path))?;
+    for kit_component in &manifest.components {
+        let Some(id) = component::ComponentId::from_str_id(&kit_component.id) else {
+            continue;
+        };
+        for instance_id in component::instances_using_component(id)? {
+            if state.process_manager.is_running(&instance_id).await {
+                return Err(AppError::instance_running());
+            }
+        }
+    }
+
+    // Unpacking and hashing a kit can take minutes; keep it off the async runtime.
+    tauri::async_runtime::spawn_blocking(move || kit::import_offline_kit(Path::new(&path)))
+        .await
+        .map_err(|e| AppError::other(format!("Offline kit import failed: {}", e)))?
 }
 
 #[tauri::command]
@@ -204,6 +491,73 @@ pub async fn uninstall_version(version: String) -> Result<()> {
     download::remove_version(&version)
 }
 
+#[tauri::command]
+pub async fn save_retention_policy(retention: RetentionPolicy) -> Result<()> {
+    with_config_mut(move |config| {
+        config.retention = retention;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn preview_gc() -> Result<GcPlan> {
+    retention::plan_gc()
+}
+
+#[tauri::command]
+pub async fn run_gc(paths: Vec<String>) -> Result<GcReport> {
+    let report = retention::run_gc(&paths)?;
+    disk_usage::request_scan();
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn refresh_disk_usage() {
+    disk_usage::request_scan();
+}
+
+#[tauri::command]
+pub fn get_data_root() -> DataRoot {
+    data_root::data_root().clone()
+}
+
+/// Move the data directory to `target`, then restart into it.
+#[tauri::command]
+pub async fn move_data_root(
+    app_handle: AppHandle,
+    target: String,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    for instance_id in state.process_manager.get_tracked_ids() {
+        if state.process_manager.is_running(&instance_id).await {
+            return Err(AppError::instance_running());
+        }
+    }
+    if download::download_manager().has_active() {
+        return Err(AppError::other(
+            "Wait for downloads to finish before moving the data directory",
+        ));
+    }
+
+    // Copying can take minutes; keep it off the async runtime.
+    tauri::async_runtime::spawn_blocking(move || data_root::move_data_root(Path::new(&target)))
+        .await
+        .map_err(|e| AppError::other(format!("Data directory move failed: {}", e)))??;
+    app_handle.restart()
+}
+
+// === Downloads ===
+
+#[tauri::command]
+pub fn list_downloads() -> Vec<download::DownloadProgress> {
+    download::download_manager().list()
+}
+
+#[tauri::command]
+pub fn cancel_download(id: String) -> bool {
+    download::download_manager().cancel(&id)
+}
+
 // === Troubleshooting ===
 
 #[tauri::command]
@@ -237,6 +591,16 @@ pub async fn create_instance(name: String, version: String, port: u16) -> Result
     instance::create_instance(&name, &version, port)
 }
 
+#[tauri::command]
+pub async fn create_dev_instance(
+    name: String,
+    source_dir: String,
+    port: u16,
+    isolate_data: bool,
+) -> Result<()> {
+    instance::create_dev_instance(&name, Path::new(&source_dir), port, isolate_data)
+}
+
 #[tauri::command]
 pub async fn delete_instance(instance_id: String, state: State<'_, AppState>) -> Result<()> {
     instance::delete_instance(&instance_id, Arc::clone(&state.process_manager)).await
@@ -265,6 +629,52 @@ pub async fn update_instance(
     .await
 }
 
+#[tauri::command]
+pub async fn set_instance_python(
+    instance_id: String,
+    python_component: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>> {
+    if state.process_manager.is_running(&instance_id).await {
+        return Err(AppError::instance_running());
+    }
+    instance::set_instance_python(&instance_id, python_component.as_deref())
+}
+
+#[tauri::command]
+pub async fn set_instance_update_policy(
+    instance_id: String,
+    update_channel: UpdateChannel,
+    auto_update: bool,
+) -> Result<()> {
+    with_config_mut(move |config| {
+        let instance = config
+            .instances
+            .get_mut(&instance_id)
+            .ok_or_else(|| AppError::instance_not_found(&instance_id))?;
+        if instance.source_dir.is_some() && auto_update {
+            return Err(AppError::config(
+                "Dev-mode instances run from their source directory and cannot auto-update",
+            ));
+        }
+        instance.update_channel = update_channel;
+        instance.auto_update = auto_update;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn check_instance_updates(
+    state: State<'_, AppState>,
+) -> Result<std::collections::HashMap<String, String>> {
+    instance::refresh_instance_updates(&state.client()).await
+}
+
+#[tauri::command]
+pub async fn set_instance_tools(instance_id: String, tools: Vec<String>) -> Result<()> {
+    instance::set_instance_tools(&instance_id, &tools)
+}
+
 #[tauri::command]
 pub async fn start_instance(
     app_handle: AppHandle,
@@ -317,8 +727,19 @@ pub async fn create_backup(instance_id: String, state: State<'_, AppState>) -> R
 }
 
 #[tauri::command]
-pub async fn restore_backup(backup_path: String) -> Result<()> {
-    backup::restore_backup(&backup_path)
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    backup_path: String,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    // Data and plugin requirements must not change under a running instance.
+    let instance_id = backup::backup_instance_id(&backup_path)?;
+    if state.process_manager.is_running(&instance_id).await {
+        return Err(AppError::instance_running());
+    }
+    backup::restore_backup(&backup_path)?;
+    instance::sync_plugin_requirements(&instance_id, &app_handle, (0, 100)).await?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -0,0 +1,232 @@
+//! Discovery of Python interpreters already present on the machine.
+//!
+//! A discovered interpreter can be registered as the provider of a Python
+//! component slot instead of downloading a managed runtime.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::error::{AppError, Result};
+
+/// Prints interpreter facts as JSON; `venv` and `ensurepip` are both needed
+/// for `python -m venv` to produce a venv with pip.
+const PROBE_SCRIPT: &str = "import importlib.util as u, json, platform; \
+print(json.dumps({'version': platform.python_version(), 'arch': platform.machine(), \
+'venv': all(u.find_spec(m) is not None for m in ('venv', 'ensurepip'))}))";
+
+/// Where an interpreter was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterpreterSource {
+    Path,
+    Pyenv,
+    Prefix,
+    /// Entered by the user.
+    Manual,
+}
+
+/// An interpreter found on the machine, as sent to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredInterpreter {
+    pub path: String,
+    /// Full version (e.g. "3.12.3").
+    pub version: String,
+    /// Machine architecture reported by the interpreter (e.g. "x86_64").
+    pub arch: String,
+    /// Whether the `venv` and `ensurepip` modules are available.
+    pub has_venv: bool,
+    pub source: InterpreterSource,
+}
+
+impl DiscoveredInterpreter {
+    /// Whether the interpreter is built for the CPU of a target triple.
+    ///
+    /// `platform.machine()` names the same CPU differently per OS, e.g.
+    /// `AMD64` on Windows and `arm64` on macOS.
+    pub fn is_built_for(&self, arch_target: &str) -> bool {
+        let arch = match self.arch.to_ascii_lowercase().as_str() {
+            "amd64" | "x64" => "x86_64".to_string(),
+            "arm64" => "aarch64".to_string(),
+            arch if arch.starts_with("armv7") => "armv7".to_string(),
+            arch => arch.to_string(),
+        };
+        arch_target.split('-').next() == Some(arch.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProbeOutput {
+    version: String,
+    arch: String,
+    venv: bool,
+}
+
+/// Run an interpreter and report its version, arch and venv support.
+pub async fn probe_interpreter(
+    path: &Path,
+    source: InterpreterSource,
+) -> Result<DiscoveredInterpreter> {
+    let output = Command::new(path)
+        .args(["-c", PROBE_SCRIPT])
+        .output()
+        .await
+        .map_err(|e| AppError::python(format!("Failed to run {:?}: {}", path, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::python(format!(
+            "Failed to probe {:?}: {}",
+            path,
+            stderr.trim()
+        )));
+    }
+
+    let probe: ProbeOutput = serde_json::from_slice(&output.stdout)?;
+    Ok(DiscoveredInterpreter {
+        path: path.to_string_lossy().into_owned(),
+        version: probe.version,
+        arch: probe.arch,
+        has_venv: probe.venv,
+        source,
+    })
+}
+
+/// Find Python interpreters on PATH, in pyenv and in common install prefixes.
+///
+/// Interpreters that fail to run are skipped.
+pub async fn discover_interpreters() -> Vec<DiscoveredInterpreter> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+
+    for (path, source) in candidate_paths() {
+        let key = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !seen.insert(key) {
+            continue;
+        }
+        match probe_interpreter(&path, source).await {
+            Ok(interpreter) => found.push(interpreter),
+            Err(e) => log::debug!("Skipping interpreter {:?}: {}", path, e),
+        }
+    }
+
+    found
+}
+
+/// Candidate interpreter paths, in discovery order.
+fn candidate_paths() -> Vec<(PathBuf, InterpreterSource)> {
+    let mut candidates = Vec::new();
+
+    if let Some(path_var) = env::var_os("PATH") {
+        for dir in env::split_paths(&path_var) {
+            for name in executable_names() {
+                candidates.push((dir.join(name), InterpreterSource::Path));
+            }
+        }
+    }
+
+    for version_dir in pyenv_version_dirs() {
+        candidates.push((pyenv_python(&version_dir), InterpreterSource::Pyenv));
+    }
+
+    for dir in prefix_dirs() {
+        for name in executable_names() {
+            candidates.push((dir.join(name), InterpreterSource::Prefix));
+        }
+    }
+
+    candidates.retain(|(path, _)| path.is_file());
+    candidates
+}
+
+#[cfg(target_os = "windows")]
+fn executable_names() -> &'static [&'static str] {
+    &["python.exe"]
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_names() -> &'static [&'static str] {
+    &[
+        "python3",
+        "python3.14",
+        "python3.13",
+        "python3.12",
+        "python3.11",
+        "python3.10",
+    ]
+}
+
+/// Version directories of a pyenv (or pyenv-win) installation.
+fn pyenv_version_dirs() -> Vec<PathBuf> {
+    let root = env::var_os("PYENV_ROOT")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".pyenv")));
+    let Some(root) = root else {
+        return Vec::new();
+    };
+
+    let versions_dir = if cfg!(target_os = "windows") {
+        root.join("pyenv-win").join("versions")
+    } else {
+        root.join("versions")
+    };
+
+    fs::read_dir(versions_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn pyenv_python(version_dir: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        version_dir.join("python.exe")
+    } else {
+        version_dir.join("bin").join("python3")
+    }
+}
+
+/// Conventional install locations not necessarily on PATH.
+#[cfg(target_os = "windows")]
+fn prefix_dirs() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(local) = dirs::data_local_dir() {
+        roots.push(local.join("Programs").join("Python"));
+    }
+    roots.push(PathBuf::from("C:\\"));
+    if let Some(program_files) = env::var_os("ProgramFiles") {
+        roots.push(PathBuf::from(program_files));
+    }
+
+    roots
+        .into_iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flat_map(|entries| entries.filter_map(|entry| entry.ok().map(|e| e.path())))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("Python3"))
+        })
+        .collect()
+}
+
+/// Conventional install locations not necessarily on PATH.
+#[cfg(not(target_os = "windows"))]
+fn prefix_dirs() -> Vec<PathBuf> {
+    [
+        "/usr/bin",
+        "/usr/local/bin",
+        "/opt/homebrew/bin",
+        "/Library/Frameworks/Python.framework/Versions/Current/bin",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect()
+}
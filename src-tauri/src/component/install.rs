@@ -0,0 +1,360 @@
+//! Registry-driven component installation.
+
+use std::future::Future;
+use std::path::Path;
+
+use reqwest::Client;
+
+use crate::archive::{extract_gz_executable, extract_tar_gz_flat, extract_zip_flat};
+use crate::checksum::{find_in_sha256sums, sha256_file, verify_sha256};
+use crate::download::{download_file, fetch_text};
+use crate::error::{AppError, Result};
+use crate::github::{fetch_repo_releases, wrap_with_proxy, GitHubRelease};
+use crate::mirror::{with_mirror_fallback, MirrorKind};
+use crate::paths::get_component_dir;
+use crate::platform::get_arch_target;
+use crate::preflight::{ensure_free_space, extracted_size};
+
+use super::lock::lock_component;
+use super::metadata::{
+    read_component_metadata, read_metadata_in, write_component_metadata, ComponentMetadata,
+};
+use super::provider::{ensure_managed, provider_executable, registered_provider};
+use super::python::query_python_version;
+use super::registry::{matches_version_spec, ArchiveFormat, ComponentKind, ComponentSource};
+use super::types::{ComponentId, ComponentStatus, ComponentsSnapshot};
+use super::update::{remove_dir_logged, sibling_dir, swap_dirs};
+
+/// Check whether a single component is installed or provided by an existing interpreter.
+pub fn is_component_installed(id: ComponentId) -> bool {
+    if let Some(exe) = provider_executable(id) {
+        return exe.exists();
+    }
+    let dir = get_component_dir(id.dir_name());
+    id.spec().executable_path(&dir).exists()
+}
+
+/// Build a snapshot of all component statuses.
+pub fn build_components_snapshot() -> ComponentsSnapshot {
+    let components = ComponentId::all()
+        .map(|id| {
+            let provider = registered_provider(id);
+            ComponentStatus {
+                id: id.dir_name().to_string(),
+                installed: is_component_installed(id),
+                version: match &provider {
+                    Some(provider) => Some(provider.version.clone()),
+                    None => read_component_metadata(id).map(|metadata| metadata.version),
+                },
+                provider: provider.map(|provider| provider.path),
+                kind: id.kind(),
+                display_name: id.display_name().to_string(),
+                description: id
+                    .spec()
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("{} 运行时", id.display_name())),
+            }
+        })
+        .collect();
+
+    ComponentsSnapshot { components }
+}
+
+/// Install a component if it is not already installed. Skips if already present.
+pub async fn install_component(client: &Client, id: ComponentId) -> Result<String> {
+    let _lock = lock_component(id)?;
+    if is_component_installed(id) {
+        return Ok(format!("{} 已安装", id.display_name()));
+    }
+
+    let target_dir = get_component_dir(id.dir_name());
+    let version = install_component_files(client, id, &target_dir).await?;
+    Ok(format!("已安装 {}: {}", id.display_name(), version))
+}
+
+/// Reinstall a component (always removes existing and re-downloads).
+pub async fn reinstall_component(client: &Client, id: ComponentId) -> Result<String> {
+    ensure_managed(id)?;
+    let _lock = lock_component(id)?;
+
+    let target_dir = get_component_dir(id.dir_name());
+    let version = install_component_files(client, id, &target_dir).await?;
+    Ok(format!("已重新安装 {}: {}", id.display_name(), version))
+}
+
+/// Fetch the releases a component is published in.
+pub(super) async fn fetch_component_releases(
+    client: &Client,
+    id: ComponentId,
+) -> Result<Vec<GitHubRelease>> {
+    match &id.spec().source {
+        ComponentSource::GitHub(repo) => fetch_repo_releases(client, repo).await,
+        ComponentSource::Url(_) => Ok(Vec::new()),
+    }
+}
+
+/// Run `op` on `url`, falling back across GitHub proxies for components
+/// published on GitHub.
+async fn with_source_url<T, F, Fut>(id: ComponentId, url: &str, mut op: F) -> Result<T>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match &id.spec().source {
+        ComponentSource::GitHub(_) => {
+            with_mirror_fallback(MirrorKind::GitHub, |proxy| op(wrap_with_proxy(&proxy, url))).await
+        }
+        ComponentSource::Url(_) => op(url.to_string()).await,
+    }
+}
+
+/// Download and extract a component into the given directory.
+pub(super) async fn install_component_files(
+    client: &Client,
+    id: ComponentId,
+    target_dir: &Path,
+) -> Result<String> {
+    let spec = id.spec();
+
+    // If target directory exists but the component is missing/corrupted, clean it first.
+    if target_dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(target_dir) {
+            log::warn!("Failed to clean component dir {:?}: {}", target_dir, e);
+        }
+    }
+
+    let releases = fetch_component_releases(client, id).await?;
+    let asset = spec.find_asset(&releases).map_err(AppError::other)?;
+
+    // Downloads may pass through third-party proxies, so nothing is installed
+    // without a known checksum.
+    let expected_sha256 = match (&spec.checksum, spec.sha256.get(&asset.name)) {
+        (_, Some(pinned)) => pinned.clone(),
+        (Some(source), None) => {
+            let sums_url = asset.checksum_url.as_deref().ok_or_else(|| {
+                AppError::other(format!("Release of {} has no {}", asset.name, source.asset))
+            })?;
+            let fetch_sums = |url: String| async move { fetch_text(client, &url).await };
+            let sums = with_source_url(id, sums_url, fetch_sums).await?;
+            let expected = find_in_sha256sums(&sums, &asset.name).ok_or_else(|| {
+                AppError::other(format!("{} is not listed in {}", asset.name, source.asset))
+            })?;
+            expected
+        }
+        (None, None) => {
+            return Err(AppError::other(format!(
+                "No checksum is known for {}, refusing to install it unverified",
+                asset.name
+            )));
+        }
+    };
+
+    let archive_path = target_dir.join(format!("component.{}", spec.archive_format().extension()));
+
+    std::fs::create_dir_all(target_dir)
+        .map_err(|e| AppError::io(format!("Failed to create component dir: {}", e)))?;
+
+    // Verified per attempt, so a proxy serving a bad file falls back to the next.
+    let download_id = format!("component:{}", id.dir_name());
+    let sha256 = with_source_url(id, &asset.url, |url| {
+        let (archive_path, download_id) = (&archive_path, &download_id);
+        let expected_sha256 = &expected_sha256;
+        async move {
+            download_file(client, &url, archive_path, download_id).await?;
+            verify_sha256(archive_path, expected_sha256).inspect_err(|_| {
+                if let Err(e) = std::fs::remove_file(archive_path) {
+                    log::warn!("Failed to remove archive {:?}: {}", archive_path, e);
+                }
+            })
+        }
+    })
+    .await?;
+
+    extract_component_archive(id, &archive_path, target_dir)?;
+
+    if let Err(e) = std::fs::remove_file(&archive_path) {
+        log::warn!("Failed to remove archive {:?}: {}", archive_path, e);
+    }
+
+    write_component_metadata(
+        target_dir,
+        &ComponentMetadata {
+            id: id.dir_name().to_string(),
+            version: asset.version.clone(),
+            asset: asset.name,
+            sha256,
+            installed_at: chrono::Utc::now().to_rfc3339(),
+        },
+    )?;
+
+    Ok(asset.version)
+}
+
+/// Install a component from a local archive instead of downloading it.
+///
+/// The archive must be the release asset the registry would download for the
+/// current platform. It is unpacked next to the current installation and only
+/// swapped in once its layout and version have been validated.
+pub async fn install_component_from_file(id: ComponentId, archive_path: &Path) -> Result<String> {
+    ensure_managed(id)?;
+    let _lock = lock_component(id)?;
+    let spec = id.spec();
+    let file_name = archive_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| AppError::other(format!("Invalid archive path: {:?}", archive_path)))?;
+
+    let extension = format!(".{}", spec.archive_format().extension());
+    if !file_name.ends_with(&extension) {
+        return Err(AppError::other(format!(
+            "{} is not a {} archive",
+            file_name, extension
+        )));
+    }
+    let arch_target = get_arch_target().map_err(AppError::other)?;
+    if !spec.is_asset_for_current_target(file_name) {
+        return Err(AppError::other(format!(
+            "{} is not built for {}",
+            file_name, arch_target
+        )));
+    }
+
+    let target_dir = get_component_dir(id.dir_name());
+    let staging_dir = sibling_dir(&target_dir, "new");
+    let backup_dir = sibling_dir(&target_dir, "old");
+    remove_dir_logged(&staging_dir);
+
+    let staged = async {
+        // Hashing and unpacking a runtime takes a while; keep it off the async runtime.
+        let (archive, staging) = (archive_path.to_path_buf(), staging_dir.clone());
+        let sha256 = tokio::task::spawn_blocking(move || {
+            let sha256 = sha256_file(&archive)?;
+            extract_component_archive(id, &archive, &staging)?;
+            Ok::<_, AppError>(sha256)
+        })
+        .await
+        .map_err(|e| AppError::other(format!("Extracting {} failed: {}", file_name, e)))??;
+        let version = local_component_version(id, file_name, &staging_dir).await?;
+        write_component_metadata(
+            &staging_dir,
+            &ComponentMetadata {
+                id: id.dir_name().to_string(),
+                version: version.clone(),
+                asset: file_name.to_string(),
+                sha256,
+                installed_at: chrono::Utc::now().to_rfc3339(),
+            },
+        )?;
+        Ok(version)
+    }
+    .await;
+
+    let version = match staged {
+        Ok(version) => version,
+        Err(e) => {
+            remove_dir_logged(&staging_dir);
+            return Err(e);
+        }
+    };
+
+    swap_dirs(&target_dir, &staging_dir, &backup_dir)?;
+    remove_dir_logged(&backup_dir);
+
+    log::info!(
+        "Installed {} {} from local archive {:?}",
+        id.dir_name(),
+        version,
+        archive_path
+    );
+    Ok(format!(
+        "已从本地文件安装 {}: {}",
+        id.display_name(),
+        version
+    ))
+}
+
+/// Install a component from an already unpacked directory, such as one taken
+/// from an offline kit.
+///
+/// The directory must carry the component's metadata and is moved into place,
+/// so it has to be on the same filesystem as the data dir.
+pub fn install_component_from_dir(id: ComponentId, source_dir: &Path) -> Result<String> {
+    ensure_managed(id)?;
+    let _lock = lock_component(id)?;
+    let spec = id.spec();
+    let exe = spec.executable_path(source_dir);
+    if !exe.exists() {
+        return Err(AppError::other(format!(
+            "{} executable not found: {:?}",
+            spec.display_name, exe
+        )));
+    }
+
+    let metadata = read_metadata_in(source_dir)
+        .ok_or_else(|| AppError::other(format!("{:?} has no component metadata", source_dir)))?;
+    if metadata.id != id.dir_name() || !matches_version_spec(&metadata.version, &spec.version) {
+        return Err(AppError::other(format!(
+            "{:?} contains {} {}, expected {} {}",
+            source_dir,
+            metadata.id,
+            metadata.version,
+            id.dir_name(),
+            spec.version
+        )));
+    }
+
+    let target_dir = get_component_dir(id.dir_name());
+    let backup_dir = sibling_dir(&target_dir, "old");
+    swap_dirs(&target_dir, source_dir, &backup_dir)?;
+    remove_dir_logged(&backup_dir);
+
+    Ok(metadata.version)
+}
+
+/// Extract a component archive into `target_dir` and check its executable.
+fn extract_component_archive(
+    id: ComponentId,
+    archive_path: &Path,
+    target_dir: &Path,
+) -> Result<()> {
+    ensure_free_space(target_dir, extracted_size(archive_path))?;
+    let spec = id.spec();
+    match spec.archive_format() {
+        ArchiveFormat::TarGz => extract_tar_gz_flat(archive_path, target_dir)?,
+        ArchiveFormat::Zip => extract_zip_flat(archive_path, target_dir)?,
+        ArchiveFormat::Gz => {
+            extract_gz_executable(archive_path, &spec.executable_path(target_dir))?
+        }
+    }
+
+    let exe = spec.executable_path(target_dir);
+    if !exe.exists() {
+        return Err(AppError::other(format!(
+            "{} extracted but executable not found: {:?}",
+            spec.display_name, exe
+        )));
+    }
+
+    Ok(())
+}
+
+/// Full version of a locally supplied component, checked against the registry.
+async fn local_component_version(id: ComponentId, file_name: &str, dir: &Path) -> Result<String> {
+    let spec = id.spec();
+    let version = match spec.kind {
+        ComponentKind::Python => query_python_version(&spec.executable_path(dir)).await?,
+        ComponentKind::Tool => spec
+            .version_from_asset_name(file_name)
+            .unwrap_or_else(|| spec.version.clone()),
+    };
+
+    if !matches_version_spec(&version, &spec.version) {
+        return Err(AppError::other(format!(
+            "{} contains version {}, expected {}",
+            file_name, version, spec.version
+        )));
+    }
+
+    Ok(version)
+}
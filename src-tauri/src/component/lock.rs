@@ -0,0 +1,40 @@
+//! Per-component locks, so operations replacing a component never overlap.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::error::{AppError, Result};
+
+use super::types::ComponentId;
+
+type ComponentLocks = HashMap<&'static str, Arc<AsyncMutex<()>>>;
+
+static LOCKS: OnceLock<Mutex<ComponentLocks>> = OnceLock::new();
+
+fn component_lock(id: ComponentId) -> Arc<AsyncMutex<()>> {
+    let mut locks = LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    Arc::clone(locks.entry(id.dir_name()).or_default())
+}
+
+/// Take the lock of a component for the lifetime of the returned guard.
+///
+/// Fails right away while another operation holds it, rather than replacing
+/// the component twice in a row.
+pub(super) fn lock_component(id: ComponentId) -> Result<OwnedMutexGuard<()>> {
+    component_lock(id).try_lock_owned().map_err(|_| {
+        AppError::other(format!(
+            "{} is already being installed or updated",
+            id.display_name()
+        ))
+    })
+}
+
+/// Whether an install or update of the component is in progress.
+pub fn is_component_locked(id: ComponentId) -> bool {
+    component_lock(id).try_lock().is_err()
+}
@@ -0,0 +1,57 @@
+//! Install metadata stored alongside each component.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Result};
+use crate::paths::get_component_dir;
+
+use super::types::ComponentId;
+
+const METADATA_FILE: &str = "component.toml";
+
+/// What was installed into a component directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentMetadata {
+    pub id: String,
+    /// Full version of the installed release (e.g. "3.12.8").
+    pub version: String,
+    /// Release asset the component was installed from.
+    pub asset: String,
+    /// SHA-256 of the downloaded archive.
+    pub sha256: String,
+    pub installed_at: String,
+}
+
+fn metadata_path(dir: &Path) -> PathBuf {
+    dir.join(METADATA_FILE)
+}
+
+/// Read the metadata of an installed component.
+///
+/// Missing for components installed by older launchers.
+pub fn read_component_metadata(id: ComponentId) -> Option<ComponentMetadata> {
+    read_metadata_in(&get_component_dir(id.dir_name()))
+}
+
+/// Read the metadata stored in a component directory.
+pub(super) fn read_metadata_in(dir: &Path) -> Option<ComponentMetadata> {
+    let path = metadata_path(dir);
+    let content = fs::read_to_string(&path).ok()?;
+    match toml::from_str(&content) {
+        Ok(metadata) => Some(metadata),
+        Err(e) => {
+            log::warn!("Failed to parse component metadata {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Write the metadata of a freshly installed component.
+pub(super) fn write_component_metadata(dir: &Path, metadata: &ComponentMetadata) -> Result<()> {
+    let content = toml::to_string_pretty(metadata)?;
+    fs::write(metadata_path(dir), content)
+        .map_err(|e| AppError::io(format!("Failed to write component metadata: {}", e)))
+}
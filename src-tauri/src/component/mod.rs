@@ -1,9 +1,36 @@
+mod discovery;
+mod install;
+mod lock;
+mod metadata;
 mod migration;
+mod provider;
 mod python;
+mod registry;
+mod requirement;
+mod tool;
 mod types;
+mod update;
 
+pub use discovery::{discover_interpreters, DiscoveredInterpreter};
+pub use install::{
+    build_components_snapshot, install_component, install_component_from_dir,
+    install_component_from_file, reinstall_component,
+};
+pub use lock::is_component_locked;
+pub use metadata::read_component_metadata;
 pub use migration::migrate_legacy_python_dirs;
+pub use provider::{
+    register_component_provider, registered_provider, unregister_component_provider,
+};
 pub use python::{
-    build_components_snapshot, get_python_for_version, install_component, reinstall_component,
+    component_for_instance, get_python_for_instance, python_compatibility_warning,
+    query_python_version, required_component_for_version,
 };
+pub use registry::ComponentKind;
+pub use requirement::read_requires_python_from_zip;
+pub use tool::{parse_tool_ids, tool_bin_dirs};
 pub use types::{ComponentId, ComponentsSnapshot};
+pub(crate) use update::remove_dir_logged;
+pub use update::{
+    check_component_updates, instances_using_component, update_component, ComponentUpdate,
+};
@@ -0,0 +1,115 @@
+//! External interpreters registered as component providers.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{load_config, with_config_mut, ComponentProvider};
+use crate::error::{AppError, Result};
+use crate::platform::get_arch_target;
+
+use super::discovery::{probe_interpreter, DiscoveredInterpreter, InterpreterSource};
+use super::registry::ComponentKind;
+use super::types::ComponentId;
+
+/// The provider registered for a component slot, if any.
+pub fn registered_provider(id: ComponentId) -> Option<ComponentProvider> {
+    load_config()
+        .ok()?
+        .component_providers
+        .get(id.dir_name())
+        .cloned()
+}
+
+/// Executable of the registered provider, if one is registered.
+pub fn provider_executable(id: ComponentId) -> Option<PathBuf> {
+    registered_provider(id).map(|provider| PathBuf::from(provider.path))
+}
+
+/// Fail when a component is served by a registered provider, so its managed
+/// files are not replaced without ever being used.
+pub(super) fn ensure_managed(id: ComponentId) -> Result<()> {
+    match registered_provider(id) {
+        Some(provider) => Err(AppError::config(format!(
+            "{} is provided by {}, unregister it first",
+            id.display_name(),
+            provider.path
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Register an existing interpreter as the provider of a Python component.
+///
+/// The interpreter must run, be built for this machine, match the
+/// component's version and ship `venv`.
+pub async fn register_component_provider(
+    id: ComponentId,
+    path: &Path,
+) -> Result<DiscoveredInterpreter> {
+    if id.kind() != ComponentKind::Python {
+        return Err(AppError::config(format!(
+            "{} cannot be provided by an interpreter",
+            id.display_name()
+        )));
+    }
+
+    let path = fs::canonicalize(path)
+        .map_err(|e| AppError::io(format!("Failed to resolve {:?}: {}", path, e)))?;
+    let interpreter = probe_interpreter(&path, InterpreterSource::Manual).await?;
+
+    // A venv built on an emulated interpreter would get wheels for the
+    // wrong architecture.
+    let arch_target = get_arch_target().map_err(AppError::other)?;
+    if !interpreter.is_built_for(arch_target) {
+        return Err(AppError::python(format!(
+            "{:?} is built for {}, expected {}",
+            path, interpreter.arch, arch_target
+        )));
+    }
+
+    let matches_version = interpreter.version == id.version()
+        || interpreter
+            .version
+            .starts_with(&format!("{}.", id.version()));
+    if !matches_version {
+        return Err(AppError::python(format!(
+            "{:?} is Python {}, expected {}",
+            path,
+            interpreter.version,
+            id.version()
+        )));
+    }
+    if !interpreter.has_venv {
+        return Err(AppError::python(format!(
+            "{:?} is missing the venv or ensurepip module",
+            path
+        )));
+    }
+
+    let key = id.dir_name().to_string();
+    let provider = ComponentProvider {
+        path: interpreter.path.clone(),
+        version: interpreter.version.clone(),
+    };
+    with_config_mut(move |config| {
+        config.component_providers.insert(key, provider);
+        Ok(())
+    })?;
+
+    log::info!(
+        "Registered {:?} (Python {}) as provider of {}",
+        path,
+        interpreter.version,
+        id.dir_name()
+    );
+    Ok(interpreter)
+}
+
+/// Remove the provider of a component, falling back to the managed runtime.
+pub fn unregister_component_provider(id: ComponentId) -> Result<()> {
+    let key = id.dir_name().to_string();
+    with_config_mut(move |config| {
+        config.component_providers.remove(&key);
+        Ok(())
+    })
+}
@@ -1,54 +1,89 @@
-use std::path::PathBuf;
+//! Python interpreter selection for instances.
 
-use reqwest::Client;
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
 
-use crate::archive::extract_tar_gz_flat;
 use crate::config::load_config;
-use crate::download::download_file;
 use crate::error::{AppError, Result};
-use crate::github::{fetch_python_releases, wrap_with_proxy};
-use crate::paths::{get_component_dir, get_python_exe_path};
-use crate::platform::find_python_asset_for_version;
+use crate::paths::get_component_dir;
 
-use super::types::{ComponentId, ComponentStatus, ComponentsSnapshot};
+use super::provider::provider_executable;
+use super::registry::ComponentKind;
+use super::requirement::{read_requires_python_from_dir, PythonRequirement};
+use super::types::ComponentId;
 
-/// Check whether a single component is installed.
-pub fn is_component_installed(id: ComponentId) -> bool {
-    let dir = get_component_dir(id.dir_name());
-    let exe = get_python_exe_path(&dir);
-    exe.exists()
-}
+/// Determine which component a given AstrBot version requires.
+///
+/// Uses the `requires-python` recorded at install time and picks the newest
+/// managed Python satisfying it. Falls back to the built-in version table
+/// when nothing was recorded or no component matches.
+pub fn required_component_for_version(version: &str) -> Result<ComponentId> {
+    if let Some(spec) = recorded_requires_python(version) {
+        match newest_component_for_requirement(&spec) {
+            Some(id) => return Ok(id),
+            None => log::warn!(
+                "No managed Python satisfies requires-python {:?} of {}, using built-in table",
+                spec,
+                version
+            ),
+        }
+    }
 
-/// Build a snapshot of all component statuses.
-pub fn build_components_snapshot() -> ComponentsSnapshot {
-    let components = ComponentId::all()
-        .iter()
-        .map(|&id| ComponentStatus {
-            id: id.dir_name().to_string(),
-            installed: is_component_installed(id),
-            display_name: id.display_name().to_string(),
-            description: format!("{} 运行时", id.display_name()),
-        })
-        .collect();
-
-    ComponentsSnapshot { components }
+    let python = if requires_python310(version) {
+        "3.10"
+    } else {
+        "3.12"
+    };
+    ComponentId::python(python)
+        .ok_or_else(|| AppError::python(format!("No Python {} component registered", python)))
 }
 
-/// Determine which component a given AstrBot version requires.
-/// v4.14.6 and earlier -> Python310, v4.14.7+ -> Python312.
-pub fn required_component_for_version(version: &str) -> ComponentId {
-    if requires_python310(version) {
-        ComponentId::Python310
-    } else {
-        ComponentId::Python312
+/// Determine which component an instance runs on.
+///
+/// The instance's `python_component` override wins over the version-derived
+/// choice. `version` is passed separately because upgrades deploy the new
+/// version before it is saved to the config. Dev-mode instances use the
+/// `requires-python` of their checkout instead of a version.
+pub fn component_for_instance(instance_id: &str, version: &str) -> Result<ComponentId> {
+    let instance = load_config()
+        .ok()
+        .and_then(|config| config.instances.get(instance_id).cloned());
+    let override_id = instance
+        .as_ref()
+        .and_then(|instance| instance.python_component.clone());
+
+    if let Some(override_id) = override_id {
+        match ComponentId::from_str_id(&override_id) {
+            Some(id) if id.kind() == ComponentKind::Python => return Ok(id),
+            _ => log::warn!(
+                "Instance {} overrides Python with unknown component {:?}, ignoring",
+                instance_id,
+                override_id
+            ),
+        }
+    }
+
+    let source_spec = instance
+        .and_then(|instance| instance.source_dir)
+        .and_then(|source_dir| read_requires_python_from_dir(Path::new(&source_dir)));
+    if let Some(id) = source_spec
+        .as_deref()
+        .and_then(newest_component_for_requirement)
+    {
+        return Ok(id);
     }
+
+    required_component_for_version(version)
 }
 
-/// Get the appropriate Python executable for a given AstrBot version.
-pub fn get_python_for_version(version: &str) -> Result<PathBuf> {
-    let id = required_component_for_version(version);
-    let dir = get_component_dir(id.dir_name());
-    let exe = get_python_exe_path(&dir);
+/// Get the Python executable an instance should be deployed with.
+///
+/// A registered provider interpreter replaces the managed runtime.
+pub fn get_python_for_instance(instance_id: &str, version: &str) -> Result<PathBuf> {
+    let id = component_for_instance(instance_id, version)?;
+    let exe = provider_executable(id)
+        .unwrap_or_else(|| id.spec().executable_path(&get_component_dir(id.dir_name())));
 
     if exe.exists() {
         Ok(exe)
@@ -57,28 +92,95 @@ pub fn get_python_for_version(version: &str) -> Result<PathBuf> {
     }
 }
 
-/// Install a component if it is not already installed. Skips if already present.
-pub async fn install_component(client: &Client, id: ComponentId) -> Result<String> {
-    if is_component_installed(id) {
-        return Ok(format!("{} 已安装", id.display_name()));
+/// Describe why `id` may not suit an AstrBot version, if it may not.
+///
+/// Overrides are allowed regardless; this only produces a warning for the user.
+pub fn python_compatibility_warning(id: ComponentId, version: &str) -> Option<String> {
+    if let Some(spec) = recorded_requires_python(version) {
+        if let Some(requirement) = PythonRequirement::parse(&spec) {
+            let allowed = parse_minor(id.version())
+                .is_some_and(|(major, minor)| requirement.allows_minor(major, minor));
+            return (!allowed).then(|| {
+                format!(
+                    "{} 要求 Python {}，{} 可能无法正常运行",
+                    version,
+                    spec,
+                    id.display_name()
+                )
+            });
+        }
     }
 
-    let target_dir = get_component_dir(id.dir_name());
-    let version = install_python_version(client, id.major_version(), &target_dir).await?;
-    Ok(format!("已安装 {}: {}", id.display_name(), version))
+    let required = required_component_for_version(version).ok()?;
+    (required != id).then(|| {
+        format!(
+            "{} 默认使用 {}，{} 可能无法正常运行",
+            version,
+            required.display_name(),
+            id.display_name()
+        )
+    })
 }
 
-/// Reinstall a component (always removes existing and re-downloads).
-pub async fn reinstall_component(client: &Client, id: ComponentId) -> Result<String> {
-    let target_dir = get_component_dir(id.dir_name());
-    let version = install_python_version(client, id.major_version(), &target_dir).await?;
-    Ok(format!("已重新安装 {}: {}", id.display_name(), version))
+/// Ask a Python interpreter for its full version (e.g. "3.12.8").
+pub async fn query_python_version(python_exe: &Path) -> Result<String> {
+    let output = Command::new(python_exe)
+        .arg("--version")
+        .output()
+        .await
+        .map_err(|e| AppError::python(format!("Failed to run {:?}: {}", python_exe, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::python(format!(
+            "Failed to query Python version: {}",
+            stderr
+        )));
+    }
+
+    // Older interpreters print the version to stderr.
+    let text = if output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    } else {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let text = text.trim();
+    Ok(text.strip_prefix("Python ").unwrap_or(text).to_string())
 }
 
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
+/// The `requires-python` recorded for an installed version, if any.
+fn recorded_requires_python(version: &str) -> Option<String> {
+    load_config().ok().and_then(|config| {
+        config
+            .installed_versions
+            .iter()
+            .find(|v| v.version == version)
+            .and_then(|v| v.requires_python.clone())
+    })
+}
+
+/// Parse a "3.12" style version into `(major, minor)`.
+fn parse_minor(version: &str) -> Option<(u64, u64)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Pick the newest component whose Python version satisfies `spec`.
+fn newest_component_for_requirement(spec: &str) -> Option<ComponentId> {
+    let requirement = PythonRequirement::parse(spec)?;
+
+    ComponentId::all()
+        .filter(|id| id.kind() == ComponentKind::Python)
+        .filter_map(|id| parse_minor(id.version()).map(|version| (version, id)))
+        .filter(|&((major, minor), _)| requirement.allows_minor(major, minor))
+        .max_by_key(|&(version, _)| version)
+        .map(|(_, id)| id)
+}
+
 /// Check if an AstrBot version requires Python 3.10 (v4.14.6 and earlier).
 fn requires_python310(version: &str) -> bool {
     let version = version.strip_prefix('v').unwrap_or(version);
@@ -91,59 +193,3 @@ fn requires_python310(version: &str) -> bool {
         _ => false,
     }
 }
-
-/// Download and install a specific Python version to the given directory.
-async fn install_python_version(
-    client: &Client,
-    major_version: &str,
-    target_dir: &PathBuf,
-) -> Result<String> {
-    // If target directory exists but runtime is missing/corrupted, clean it first.
-    if target_dir.exists() {
-        if let Err(e) = std::fs::remove_dir_all(target_dir) {
-            log::warn!("Failed to clean python dir {:?}: {}", target_dir, e);
-        }
-    }
-
-    let releases = fetch_python_releases(client).await?;
-
-    let mut download_url = None;
-    let mut python_version = String::new();
-
-    for release in &releases {
-        if let Ok((url, version)) = find_python_asset_for_version(&release.assets, major_version) {
-            download_url = Some(url);
-            python_version = version;
-            break;
-        }
-    }
-
-    let mut url = download_url.ok_or_else(|| AppError::python(major_version.to_string()))?;
-
-    if let Ok(config) = load_config() {
-        url = wrap_with_proxy(&config.github_proxy, &url);
-    }
-
-    let archive_path = target_dir.join("python.tar.gz");
-
-    std::fs::create_dir_all(target_dir)
-        .map_err(|e| AppError::io(format!("Failed to create python dir: {}", e)))?;
-
-    download_file(client, &url, &archive_path).await?;
-
-    extract_tar_gz_flat(&archive_path, target_dir)?;
-
-    let python_exe = get_python_exe_path(target_dir);
-    if !python_exe.exists() {
-        return Err(AppError::python(format!(
-            "Python {} extracted but executable not found: {:?}",
-            major_version, python_exe
-        )));
-    }
-
-    if let Err(e) = std::fs::remove_file(&archive_path) {
-        log::warn!("Failed to remove archive {:?}: {}", archive_path, e);
-    }
-
-    Ok(python_version)
-}
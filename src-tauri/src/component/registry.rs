@@ -0,0 +1,355 @@
+//! Data-driven component registry.
+//!
+//! Components are described by the embedded `registry.toml` manifest, so a
+//! new runtime or tool only needs a manifest entry.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::github::GitHubRelease;
+use crate::platform::get_arch_target;
+
+const REGISTRY_MANIFEST: &str = include_str!("registry.toml");
+
+static REGISTRY: OnceLock<Vec<ComponentSpec>> = OnceLock::new();
+
+/// What a component is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentKind {
+    /// An interpreter instance venvs can be created from.
+    Python,
+    /// A standalone executable made available to instances.
+    Tool,
+}
+
+/// Where component releases are published.
+#[derive(Debug, Clone, Deserialize)]
+pub enum ComponentSource {
+    /// GitHub releases of `owner/repo`.
+    #[serde(rename = "github")]
+    GitHub(String),
+    /// A download directory, with `{version}` replaced by the pinned version.
+    #[serde(rename = "url")]
+    Url(String),
+}
+
+/// Archive format of a component asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ArchiveFormat {
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    #[serde(rename = "zip")]
+    Zip,
+    /// A single gzip-compressed executable.
+    #[serde(rename = "gz")]
+    Gz,
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+            Self::Gz => "gz",
+        }
+    }
+}
+
+/// Release asset listing SHA-256 sums of the other assets.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChecksumSource {
+    pub asset: String,
+}
+
+/// Main executable path inside the component directory, per OS family.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutablePath {
+    pub windows: String,
+    pub unix: String,
+}
+
+/// A single registry entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComponentSpec {
+    pub id: String,
+    pub kind: ComponentKind,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub version: String,
+    pub source: ComponentSource,
+    #[serde(default)]
+    pub asset: Option<String>,
+    /// Per-target overrides of `asset`, keyed by `get_arch_target()`.
+    #[serde(default)]
+    pub assets: HashMap<String, String>,
+    pub archive: ArchiveFormat,
+    /// Per-target overrides of `archive`, keyed by `get_arch_target()`.
+    #[serde(default)]
+    pub archives: HashMap<String, ArchiveFormat>,
+    #[serde(default)]
+    pub checksum: Option<ChecksumSource>,
+    /// Pinned SHA-256 by asset name, for sources without a checksum listing.
+    #[serde(default)]
+    pub sha256: HashMap<String, String>,
+    pub executable: ExecutablePath,
+}
+
+/// A release asset matching a component on the current platform.
+#[derive(Debug, Clone)]
+pub struct ComponentAsset {
+    pub name: String,
+    pub url: String,
+    /// Full version, taken from the asset name or the release tag.
+    pub version: String,
+    /// Checksum listing published in the same release, if any.
+    pub checksum_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryManifest {
+    component: Vec<ComponentSpec>,
+}
+
+/// All registered components, in manifest order.
+pub(super) fn registry() -> &'static [ComponentSpec] {
+    REGISTRY.get_or_init(
+        || match toml::from_str::<RegistryManifest>(REGISTRY_MANIFEST) {
+            Ok(manifest) => manifest.component,
+            Err(e) => {
+                log::error!("Failed to parse component registry: {}", e);
+                Vec::new()
+            }
+        },
+    )
+}
+
+impl ComponentSpec {
+    /// Path of the main executable inside `dir`.
+    pub fn executable_path(&self, dir: &Path) -> PathBuf {
+        if cfg!(target_os = "windows") {
+            dir.join(&self.executable.windows)
+        } else {
+            dir.join(&self.executable.unix)
+        }
+    }
+
+    /// Directory holding the main executable, prepended to PATH for tools.
+    pub fn bin_dir(&self, dir: &Path) -> PathBuf {
+        let exe = self.executable_path(dir);
+        exe.parent()
+            .map_or_else(|| dir.to_path_buf(), Path::to_path_buf)
+    }
+
+    /// Archive format of the asset for the current platform.
+    pub fn archive_format(&self) -> ArchiveFormat {
+        get_arch_target()
+            .ok()
+            .and_then(|arch_target| self.archives.get(arch_target).copied())
+            .unwrap_or(self.archive)
+    }
+
+    /// Asset name template for the current platform.
+    fn asset_template(&self) -> Result<(&'static str, &str), String> {
+        let arch_target = get_arch_target()?;
+        let template = self
+            .assets
+            .get(arch_target)
+            .or(self.asset.as_ref())
+            .ok_or_else(|| format!("{} is not available for {}", self.display_name, arch_target))?;
+        Ok((arch_target, template))
+    }
+
+    /// Version captured from an asset file name, if it matches this
+    /// component's asset template for the current platform.
+    pub fn version_from_asset_name(&self, name: &str) -> Option<String> {
+        let (arch_target, template) = self.asset_template().ok()?;
+        match_asset(template, arch_target, &self.version, name)?
+    }
+
+    /// Whether an asset file name was built for the current platform.
+    ///
+    /// Templates naming the target triple only need the triple to appear, so
+    /// other builds for the same platform are accepted as well.
+    pub fn is_asset_for_current_target(&self, name: &str) -> bool {
+        let Ok((arch_target, template)) = self.asset_template() else {
+            return false;
+        };
+        if template.contains("{arch}") {
+            name.contains(arch_target)
+        } else {
+            match_asset(template, arch_target, &self.version, name).is_some()
+        }
+    }
+
+    /// Find the newest asset for the current platform across `releases`.
+    ///
+    /// Components with a URL source are pinned and ignore `releases`.
+    pub fn find_asset(&self, releases: &[GitHubRelease]) -> Result<ComponentAsset, String> {
+        let (arch_target, template) = self.asset_template()?;
+
+        if let ComponentSource::Url(base) = &self.source {
+            let base = base.replace("{version}", &self.version);
+            let base = base.trim_end_matches('/');
+            let name = template
+                .replace("{arch}", arch_target)
+                .replace("{version}", &self.version);
+            return Ok(ComponentAsset {
+                url: format!("{}/{}", base, name),
+                name,
+                version: self.version.clone(),
+                checksum_url: self
+                    .checksum
+                    .as_ref()
+                    .map(|checksum| format!("{}/{}", base, checksum.asset)),
+            });
+        }
+
+        releases
+            .iter()
+            .find_map(|release| {
+                release.assets.iter().find_map(|asset| {
+                    let captured = match_asset(template, arch_target, &self.version, &asset.name)?;
+                    // Without a version in the asset name, the tag (e.g. "v6.1" or
+                    // "b6.1") carries it and must match the spec instead.
+                    let version = match captured {
+                        Some(version) => version,
+                        None => {
+                            let tag = release
+                                .tag_name
+                                .trim_start_matches(|c: char| !c.is_ascii_digit());
+                            if !matches_version_spec(tag, &self.version) {
+                                return None;
+                            }
+                            tag.to_string()
+                        }
+                    };
+                    Some(ComponentAsset {
+                        name: asset.name.clone(),
+                        url: asset.browser_download_url.clone(),
+                        version,
+                        checksum_url: self.checksum.as_ref().and_then(|checksum| {
+                            release
+                                .assets
+                                .iter()
+                                .find(|a| a.name == checksum.asset)
+                                .map(|a| a.browser_download_url.clone())
+                        }),
+                    })
+                })
+            })
+            .ok_or_else(|| {
+                format!(
+                    "No {} asset found for platform {}",
+                    self.display_name, arch_target
+                )
+            })
+    }
+}
+
+/// Match an asset name against a template.
+///
+/// Returns `Some(version)` on a match, where `version` is the text captured by
+/// `{version}` if the template has one.
+fn match_asset(
+    template: &str,
+    arch_target: &str,
+    version_spec: &str,
+    name: &str,
+) -> Option<Option<String>> {
+    let template = template.replace("{arch}", arch_target);
+
+    let Some((prefix, rest)) = template.split_once("{version}") else {
+        return glob_match(&template, name).then_some(None);
+    };
+
+    let tail = name.strip_prefix(prefix)?;
+    let end = tail
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(tail.len());
+    let version = tail[..end].trim_end_matches('.');
+
+    if !matches_version_spec(version, version_spec) || !glob_match(rest, &tail[version.len()..]) {
+        return None;
+    }
+
+    Some(Some(version.to_string()))
+}
+
+/// Whether a full version (e.g. "3.12.8") falls under a registry version spec (e.g. "3.12").
+pub(super) fn matches_version_spec(version: &str, version_spec: &str) -> bool {
+    version == version_spec || version.starts_with(&format!("{}.", version_spec))
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let Some((head, tail)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(rest) = text.strip_prefix(head) else {
+        return false;
+    };
+
+    (0..=rest.len())
+        .filter(|&i| rest.is_char_boundary(i))
+        .any(|i| glob_match(tail, &rest[i..]))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_manifest_parses() {
+        let manifest: RegistryManifest = toml::from_str(REGISTRY_MANIFEST).unwrap();
+        assert!(!manifest.component.is_empty());
+        assert_eq!(registry().len(), manifest.component.len());
+    }
+
+    #[test]
+    fn component_ids_are_unique() {
+        let mut ids: Vec<&str> = registry().iter().map(|spec| spec.id.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), registry().len());
+    }
+
+    #[test]
+    fn pinned_checksums_are_sha256() {
+        for spec in registry() {
+            for (asset, sha256) in &spec.sha256 {
+                assert!(
+                    sha256.len() == 64 && sha256.bytes().all(|b| b.is_ascii_hexdigit()),
+                    "{}: bad sha256 for {}",
+                    spec.id,
+                    asset
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn version_spec_matches_whole_components() {
+        assert!(matches_version_spec("3.12", "3.12"));
+        assert!(matches_version_spec("3.12.8", "3.12"));
+        assert!(!matches_version_spec("3.120.1", "3.12"));
+    }
+
+    #[test]
+    fn glob_matches_any_run() {
+        assert!(glob_match(
+            "node-v*-linux-x64.tar.gz",
+            "node-v22.1.0-linux-x64.tar.gz"
+        ));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match(
+            "node-v*-linux-x64.tar.gz",
+            "node-v22.1.0-win-x64.zip"
+        ));
+    }
+}
@@ -0,0 +1,265 @@
+//! `requires-python` handling for AstrBot sources.
+//!
+//! Only the subset of PEP 440 specifiers that appears in practice is
+//! supported: comparison operators, `~=`, and `.*` wildcards.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::archive::read_zip_root_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Compatible,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Clause {
+    op: Operator,
+    release: Vec<u64>,
+    wildcard: bool,
+}
+
+/// A parsed `requires-python` specifier set, e.g. `>=3.10,<3.14`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonRequirement {
+    clauses: Vec<Clause>,
+}
+
+impl PythonRequirement {
+    /// Parse a specifier set. Returns `None` if any clause is not understood.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let clauses = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|clause| !clause.is_empty())
+            .map(parse_clause)
+            .collect::<Option<Vec<_>>>()?;
+
+        if clauses.is_empty() {
+            None
+        } else {
+            Some(Self { clauses })
+        }
+    }
+
+    /// Whether the newest patch release of `major.minor` satisfies the requirement.
+    ///
+    /// Managed runtimes always track the latest patch of a minor series.
+    pub fn allows_minor(&self, major: u64, minor: u64) -> bool {
+        let candidate = [major, minor, u64::MAX];
+        self.clauses
+            .iter()
+            .all(|clause| clause_matches(clause, &candidate))
+    }
+}
+
+fn parse_clause(clause: &str) -> Option<Clause> {
+    const OPERATORS: &[(&str, Operator)] = &[
+        ("~=", Operator::Compatible),
+        ("==", Operator::Eq),
+        ("!=", Operator::NotEq),
+        ("<=", Operator::LtEq),
+        (">=", Operator::GtEq),
+        ("<", Operator::Lt),
+        (">", Operator::Gt),
+    ];
+
+    let (op, rest) = OPERATORS
+        .iter()
+        .find_map(|(prefix, op)| clause.strip_prefix(prefix).map(|rest| (*op, rest)))?;
+
+    let rest = rest.trim();
+    let (version, wildcard) = match rest.strip_suffix(".*") {
+        Some(version) => (version, true),
+        None => (rest, false),
+    };
+    if wildcard && !matches!(op, Operator::Eq | Operator::NotEq) {
+        return None;
+    }
+
+    let release = version
+        .split('.')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if release.is_empty() || (op == Operator::Compatible && release.len() < 2) {
+        return None;
+    }
+
+    Some(Clause {
+        op,
+        release,
+        wildcard,
+    })
+}
+
+fn clause_matches(clause: &Clause, candidate: &[u64]) -> bool {
+    let ordering = compare_release(candidate, &clause.release);
+    let prefix_matches = candidate.iter().zip(&clause.release).all(|(a, b)| a == b)
+        && candidate.len() >= clause.release.len();
+
+    match clause.op {
+        Operator::Eq if clause.wildcard => prefix_matches,
+        Operator::NotEq if clause.wildcard => !prefix_matches,
+        Operator::Eq => ordering.is_eq(),
+        Operator::NotEq => !ordering.is_eq(),
+        Operator::Lt => ordering.is_lt(),
+        Operator::LtEq => ordering.is_le(),
+        Operator::Gt => ordering.is_gt(),
+        Operator::GtEq => ordering.is_ge(),
+        Operator::Compatible => {
+            // ~=X.Y.Z means >=X.Y.Z, ==X.Y.*
+            let prefix = &clause.release[..clause.release.len() - 1];
+            ordering.is_ge() && candidate.iter().zip(prefix).all(|(a, b)| a == b)
+        }
+    }
+}
+
+/// Compare release segments, padding the shorter one with zeros.
+fn compare_release(a: &[u64], b: &[u64]) -> std::cmp::Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            let x = a.get(i).copied().unwrap_or(0);
+            let y = b.get(i).copied().unwrap_or(0);
+            x.cmp(&y)
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+#[derive(Debug, Deserialize)]
+struct Pyproject {
+    #[serde(default)]
+    project: Option<PyprojectProject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PyprojectProject {
+    #[serde(default, rename = "requires-python")]
+    requires_python: Option<String>,
+}
+
+/// Extract `[project].requires-python` from pyproject.toml contents.
+pub fn parse_requires_python(pyproject: &str) -> Option<String> {
+    let parsed: Pyproject = toml::from_str(pyproject).ok()?;
+    parsed
+        .project?
+        .requires_python
+        .map(|spec| spec.trim().to_string())
+        .filter(|spec| !spec.is_empty())
+}
+
+/// Turn a `.python-version` file (e.g. "3.12") into an equivalent specifier.
+fn python_version_file_to_spec(content: &str) -> Option<String> {
+    let version = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse::<u64>().ok()?;
+    let minor = parts.next()?.parse::<u64>().ok()?;
+    Some(format!("=={}.{}.*", major, minor))
+}
+
+/// Read the Python requirement of an AstrBot source zip.
+///
+/// Looks at `pyproject.toml` first and falls back to `.python-version`.
+pub fn read_requires_python_from_zip(zip_path: &Path) -> Option<String> {
+    let from_pyproject = match read_zip_root_file(zip_path, "pyproject.toml") {
+        Ok(content) => content.as_deref().and_then(parse_requires_python),
+        Err(e) => {
+            log::warn!("Failed to read pyproject.toml from {:?}: {}", zip_path, e);
+            None
+        }
+    };
+
+    from_pyproject.or_else(|| {
+        read_zip_root_file(zip_path, ".python-version")
+            .ok()
+            .flatten()
+            .as_deref()
+            .and_then(python_version_file_to_spec)
+    })
+}
+
+/// Read the Python requirement of an AstrBot source checkout.
+///
+/// Looks at `pyproject.toml` first and falls back to `.python-version`.
+pub fn read_requires_python_from_dir(source_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(source_dir.join("pyproject.toml"))
+        .ok()
+        .as_deref()
+        .and_then(parse_requires_python)
+        .or_else(|| {
+            std::fs::read_to_string(source_dir.join(".python-version"))
+                .ok()
+                .as_deref()
+                .and_then(python_version_file_to_spec)
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn allowed(spec: &str) -> Vec<u64> {
+        let requirement = PythonRequirement::parse(spec).unwrap();
+        (8..=14)
+            .filter(|&minor| requirement.allows_minor(3, minor))
+            .collect()
+    }
+
+    #[test]
+    fn compatible_release() {
+        assert_eq!(allowed("~=3.10"), [10, 11, 12, 13, 14]);
+        assert_eq!(allowed("~=3.10.2"), [10]);
+    }
+
+    #[test]
+    fn exclusions() {
+        // `!=3.11` only excludes 3.11.0, not the latest 3.11 patch.
+        assert_eq!(allowed("!=3.11"), [8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(allowed("!=3.11.*"), [8, 9, 10, 12, 13, 14]);
+    }
+
+    #[test]
+    fn bounded_range() {
+        assert_eq!(allowed(">=3.10,<3.14"), [10, 11, 12, 13]);
+        assert_eq!(allowed(">=3.10, <3.13, !=3.11.*"), [10, 12]);
+        // Later patches of 3.9 are above 3.9, and of 3.12 above 3.12.
+        assert_eq!(allowed(">3.9,<=3.12"), [9, 10, 11]);
+    }
+
+    #[test]
+    fn wildcards() {
+        assert_eq!(allowed("==3.12.*"), [12]);
+        assert_eq!(allowed("==3.*"), [8, 9, 10, 11, 12, 13, 14]);
+    }
+
+    #[test]
+    fn rejects_unsupported_specifiers() {
+        for spec in ["", " , ", "~=3", ">=3.*", ">=3.x", "3.10", "===3.10"] {
+            assert_eq!(PythonRequirement::parse(spec), None, "{:?}", spec);
+        }
+    }
+
+    #[test]
+    fn reads_version_sources() {
+        let pyproject = "[project]\nname = \"AstrBot\"\nrequires-python = \" >=3.10 \"\n";
+        assert_eq!(parse_requires_python(pyproject).as_deref(), Some(">=3.10"));
+        assert_eq!(parse_requires_python("[project]\nname = \"x\"\n"), None);
+        assert_eq!(
+            python_version_file_to_spec("# pinned\n3.12.4\n").as_deref(),
+            Some("==3.12.*")
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Tool components made available to instances that opt in.
+
+use std::path::PathBuf;
+
+use crate::error::{AppError, Result};
+use crate::paths::get_component_dir;
+
+use super::install::is_component_installed;
+use super::registry::ComponentKind;
+use super::types::ComponentId;
+
+/// Resolve tool component ids to known tool components.
+pub fn parse_tool_ids(tools: &[String]) -> Result<Vec<ComponentId>> {
+    tools
+        .iter()
+        .map(|tool| {
+            ComponentId::from_str_id(tool)
+                .filter(|id| id.kind() == ComponentKind::Tool)
+                .ok_or_else(|| AppError::config(format!("Unknown tool component: {}", tool)))
+        })
+        .collect()
+}
+
+/// Directories to prepend to PATH for an instance's tools.
+///
+/// Fails if an opted-in tool is not installed, rather than letting plugins
+/// hit a missing executable at runtime. Tools no longer in the registry are
+/// skipped, so instances that opted into one can still start.
+pub fn tool_bin_dirs(tools: &[String]) -> Result<Vec<PathBuf>> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            let id = ComponentId::from_str_id(tool).filter(|id| id.kind() == ComponentKind::Tool);
+            if id.is_none() {
+                log::warn!("Skipping unknown tool component {}", tool);
+            }
+            id
+        })
+        .map(|id| {
+            if !is_component_installed(id) {
+                return Err(AppError::other(format!(
+                    "{} is not installed, install it from the versions page",
+                    id.display_name()
+                )));
+            }
+            Ok(id.spec().bin_dir(&get_component_dir(id.dir_name())))
+        })
+        .collect()
+}
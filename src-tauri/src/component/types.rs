@@ -1,49 +1,57 @@
 use serde::Serialize;
 
-/// Identifies a managed component.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ComponentId {
-    Python312,
-    Python310,
+use super::registry::{registry, ComponentKind, ComponentSpec};
+
+/// Identifies a managed component in the registry.
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentId(&'static ComponentSpec);
+
+impl PartialEq for ComponentId {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
 }
 
+impl Eq for ComponentId {}
+
 impl ComponentId {
     /// Directory name under `components/`.
     pub fn dir_name(self) -> &'static str {
-        match self {
-            Self::Python312 => "python312",
-            Self::Python310 => "python310",
-        }
+        &self.0.id
     }
 
     /// Human-readable display name.
     pub fn display_name(self) -> &'static str {
-        match self {
-            Self::Python312 => "Python 3.12",
-            Self::Python310 => "Python 3.10",
-        }
+        &self.0.display_name
+    }
+
+    /// Version spec from the registry (e.g. "3.12").
+    pub fn version(self) -> &'static str {
+        &self.0.version
+    }
+
+    pub fn kind(self) -> ComponentKind {
+        self.0.kind
     }
 
-    /// Major version string used by the download system (e.g. "3.12").
-    pub fn major_version(self) -> &'static str {
-        match self {
-            Self::Python312 => "3.12",
-            Self::Python310 => "3.10",
-        }
+    /// Full registry entry.
+    pub fn spec(self) -> &'static ComponentSpec {
+        self.0
     }
 
     /// Parse a string id (e.g. from the frontend) into a `ComponentId`.
     pub fn from_str_id(s: &str) -> Option<Self> {
-        match s {
-            "python312" => Some(Self::Python312),
-            "python310" => Some(Self::Python310),
-            _ => None,
-        }
+        registry().iter().find(|spec| spec.id == s).map(Self)
+    }
+
+    /// The Python component for a minor version (e.g. "3.10").
+    pub fn python(version: &str) -> Option<Self> {
+        Self::all().find(|id| id.kind() == ComponentKind::Python && id.version() == version)
     }
 
-    /// All known component ids.
-    pub fn all() -> &'static [Self] {
-        &[Self::Python312, Self::Python310]
+    /// All registered component ids.
+    pub fn all() -> impl Iterator<Item = Self> {
+        registry().iter().map(Self)
     }
 }
 
@@ -52,6 +60,11 @@ impl ComponentId {
 pub struct ComponentStatus {
     pub id: String,
     pub installed: bool,
+    /// Installed release version, if known.
+    pub version: Option<String>,
+    /// Path of the external interpreter providing this component, if any.
+    pub provider: Option<String>,
+    pub kind: ComponentKind,
     pub display_name: String,
     pub description: String,
 }
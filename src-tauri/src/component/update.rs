@@ -0,0 +1,197 @@
+//! Update detection and atomic replacement of installed components.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::config::load_config;
+use crate::error::{AppError, Result};
+use crate::github::GitHubRelease;
+use crate::paths::get_component_dir;
+
+use super::install::{fetch_component_releases, install_component_files, is_component_installed};
+use super::lock::lock_component;
+use super::metadata::read_component_metadata;
+use super::provider::{ensure_managed, registered_provider};
+use super::python::{component_for_instance, query_python_version};
+use super::registry::ComponentKind;
+use super::types::ComponentId;
+
+/// A newer release of an installed component.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentUpdate {
+    pub id: String,
+    pub display_name: String,
+    pub current_version: String,
+    pub latest_version: String,
+}
+
+/// Installed full version of a managed component.
+///
+/// Falls back to asking the interpreter for Python components installed
+/// before metadata was recorded.
+async fn installed_version(id: ComponentId) -> Option<String> {
+    if let Some(metadata) = read_component_metadata(id) {
+        return Some(metadata.version);
+    }
+    if id.kind() != ComponentKind::Python {
+        return None;
+    }
+    let exe = id.spec().executable_path(&get_component_dir(id.dir_name()));
+    query_python_version(&exe).await.ok()
+}
+
+/// Whether `latest` is a newer release than `current`.
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (
+        semver::Version::parse(latest),
+        semver::Version::parse(current),
+    ) {
+        (Ok(latest), Ok(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Check installed managed components for newer builds.
+///
+/// Components served by a registered provider interpreter are skipped.
+pub async fn check_component_updates(client: &Client) -> Result<Vec<ComponentUpdate>> {
+    let mut releases_by_source: HashMap<String, Vec<GitHubRelease>> = HashMap::new();
+    let mut updates = Vec::new();
+
+    for id in ComponentId::all() {
+        if registered_provider(id).is_some() || !is_component_installed(id) {
+            continue;
+        }
+        let Some(current) = installed_version(id).await else {
+            continue;
+        };
+
+        let source_key = format!("{:?}", id.spec().source);
+        let releases = match releases_by_source.get(&source_key) {
+            Some(releases) => releases,
+            None => {
+                let fetched = fetch_component_releases(client, id).await?;
+                releases_by_source.entry(source_key).or_insert(fetched)
+            }
+        };
+
+        match id.spec().find_asset(releases) {
+            Ok(asset) if is_newer(&asset.version, &current) => updates.push(ComponentUpdate {
+                id: id.dir_name().to_string(),
+                display_name: id.display_name().to_string(),
+                current_version: current,
+                latest_version: asset.version,
+            }),
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to check updates for {}: {}", id.dir_name(), e),
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Instances whose venv is built on the given component, or that opted into
+/// the given tool.
+pub fn instances_using_component(id: ComponentId) -> Result<Vec<String>> {
+    let config = load_config()?;
+    Ok(config
+        .instances
+        .iter()
+        .filter(|(instance_id, instance)| match id.kind() {
+            ComponentKind::Python => {
+                component_for_instance(instance_id, &instance.version).is_ok_and(|c| c == id)
+            }
+            ComponentKind::Tool => instance.tools.iter().any(|tool| tool == id.dir_name()),
+        })
+        .map(|(instance_id, _)| instance_id.clone())
+        .collect())
+}
+
+/// Download the latest build of a component and swap it in atomically.
+///
+/// The new build is staged next to the current one and moved into place with
+/// renames, so a failed download never leaves the component half-replaced.
+/// Dependent venvs are rebuilt on their next start, as their deploy
+/// fingerprint no longer matches the interpreter.
+pub async fn update_component(client: &Client, id: ComponentId) -> Result<String> {
+    ensure_managed(id)?;
+    let _lock = lock_component(id)?;
+
+    let target_dir = get_component_dir(id.dir_name());
+    let staging_dir = sibling_dir(&target_dir, "new");
+    let backup_dir = sibling_dir(&target_dir, "old");
+
+    let previous = installed_version(id).await.unwrap_or_default();
+    let version = match install_component_files(client, id, &staging_dir).await {
+        Ok(version) => version,
+        Err(e) => {
+            remove_dir_logged(&staging_dir);
+            return Err(e);
+        }
+    };
+
+    swap_dirs(&target_dir, &staging_dir, &backup_dir)?;
+    remove_dir_logged(&backup_dir);
+
+    let dependents = instances_using_component(id)?;
+    log::info!(
+        "Updated {} from {} to {}, {} instance venv(s) will be rebuilt",
+        id.dir_name(),
+        previous,
+        version,
+        dependents.len()
+    );
+
+    Ok(format!(
+        "已更新 {}: {} -> {}，{} 个实例将在下次启动时重建环境",
+        id.display_name(),
+        previous,
+        version,
+        dependents.len()
+    ))
+}
+
+pub(super) fn sibling_dir(dir: &Path, suffix: &str) -> PathBuf {
+    let mut name = dir.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", suffix));
+    dir.with_file_name(name)
+}
+
+/// Move `staging` into `target`, keeping the old contents in `backup` until
+/// the swap succeeded.
+pub(super) fn swap_dirs(target: &Path, staging: &Path, backup: &Path) -> Result<()> {
+    remove_dir_logged(backup);
+
+    let had_target = target.exists();
+    if had_target {
+        fs::rename(target, backup)
+            .map_err(|e| AppError::io(format!("Failed to move {:?} aside: {}", target, e)))?;
+    }
+
+    if let Err(e) = fs::rename(staging, target) {
+        if had_target {
+            if let Err(restore_err) = fs::rename(backup, target) {
+                log::error!("Failed to restore {:?}: {}", target, restore_err);
+            }
+        }
+        return Err(AppError::io(format!(
+            "Failed to move {:?} into place: {}",
+            staging, e
+        )));
+    }
+
+    Ok(())
+}
+
+/// Remove a directory tree, logging instead of failing when that fails.
+pub(crate) fn remove_dir_logged(dir: &Path) {
+    if dir.exists() {
+        if let Err(e) = fs::remove_dir_all(dir) {
+            log::warn!("Failed to remove {:?}: {}", dir, e);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,9 @@ use crate::paths::{config_path, ensure_data_dirs};
 
 static CONFIG_LOCK: Mutex<()> = Mutex::new(());
 static CONFIG_CACHE: OnceLock<RwLock<Arc<AppConfig>>> = OnceLock::new();
+/// Set once the config has moved to another data root; writing it here
+/// would recreate the old one.
+static CONFIG_FROZEN: AtomicBool = AtomicBool::new(false);
 
 fn load_config_from_disk() -> Result<AppConfig> {
     let path = config_path();
@@ -18,7 +22,10 @@ fn load_config_from_disk() -> Result<AppConfig> {
         return Ok(config);
     }
     let content = fs::read_to_string(&path).map_err(|e| AppError::config(e.to_string()))?;
-    toml::from_str(&content).map_err(|e| AppError::config(e.to_string()))
+    let mut config: AppConfig =
+        toml::from_str(&content).map_err(|e| AppError::config(e.to_string()))?;
+    config.migrate_legacy_mirrors();
+    Ok(config)
 }
 
 fn save_config_to_disk(config: &AppConfig) -> Result<()> {
@@ -47,6 +54,11 @@ where
     F: FnOnce(&mut AppConfig) -> Result<T>,
 {
     let _guard = CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if CONFIG_FROZEN.load(Ordering::SeqCst) {
+        return Err(AppError::config(
+            "Config is read-only until the launcher restarts",
+        ));
+    }
     let cache = get_config_cache()?;
 
     let current = {
@@ -69,39 +81,140 @@ pub struct AppConfig {
     pub instances: HashMap<String, InstanceConfig>,
     #[serde(default)]
     pub installed_versions: Vec<InstalledVersion>,
+    /// GitHub proxies, tried in order before the official address.
+    #[serde(default)]
+    pub github_proxies: Vec<String>,
+    /// PyPI index mirrors, tried in order before the official index.
     #[serde(default)]
-    pub github_proxy: String,
+    pub pypi_mirrors: Vec<String>,
+    /// Personal access token for the GitHub API, only sent without a GitHub proxy.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub github_token: String,
+    /// HTTP/SOCKS proxy for the launcher's downloads and pip.
     #[serde(default)]
-    pub pypi_mirror: String,
+    pub proxy: ProxyConfig,
+    /// Extra source repositories ("owner/repo") versions can be installed from.
+    #[serde(default)]
+    pub source_repositories: Vec<String>,
+    /// Single proxy of older configs, moved into `github_proxies` on load.
+    #[serde(default, skip_serializing)]
+    github_proxy: String,
+    /// Single mirror of older configs, moved into `pypi_mirrors` on load.
+    #[serde(default, skip_serializing)]
+    pypi_mirror: String,
     #[serde(default = "default_true")]
     pub close_to_tray: bool,
     #[serde(default = "default_true")]
     pub check_instance_update: bool,
+    /// Local hour (0-23) in which instances opted into auto-update are updated.
+    #[serde(default = "default_auto_update_hour")]
+    pub auto_update_hour: u8,
     #[serde(default)]
     pub persist_instance_state: bool,
     #[serde(default)]
     pub tracked_instances_snapshot: Vec<String>,
+    /// External interpreters used instead of managed runtimes, keyed by component id.
+    #[serde(default)]
+    pub component_providers: HashMap<String, ComponentProvider>,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_auto_update_hour() -> u8 {
+    4
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             instances: HashMap::new(),
             installed_versions: Vec::new(),
+            github_proxies: Vec::new(),
+            pypi_mirrors: Vec::new(),
+            github_token: String::new(),
+            proxy: ProxyConfig::default(),
+            source_repositories: Vec::new(),
             github_proxy: String::new(),
             pypi_mirror: String::new(),
             close_to_tray: true,
             check_instance_update: true,
+            auto_update_hour: default_auto_update_hour(),
             persist_instance_state: false,
             tracked_instances_snapshot: Vec::new(),
+            component_providers: HashMap::new(),
+            retention: RetentionPolicy::default(),
         }
     }
 }
 
+impl AppConfig {
+    fn migrate_legacy_mirrors(&mut self) {
+        let github_proxy = std::mem::take(&mut self.github_proxy);
+        if !github_proxy.is_empty() && self.github_proxies.is_empty() {
+            self.github_proxies.push(github_proxy);
+        }
+        let pypi_mirror = std::mem::take(&mut self.pypi_mirror);
+        if !pypi_mirror.is_empty() && self.pypi_mirrors.is_empty() {
+            self.pypi_mirrors.push(pypi_mirror);
+        }
+    }
+}
+
+/// Proxy for outgoing HTTP traffic.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// `http`, `https`, `socks5` or `socks5h` URL; empty uses the system settings.
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+    /// Hosts, domains and IP ranges that bypass the proxy.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+/// What garbage collection keeps. A rule set to 0 is disabled.
+///
+/// Versions used by an instance are always kept. With both version rules
+/// disabled, only archives no longer listed as installed are removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Keep the most recently installed versions.
+    pub keep_latest_versions: u32,
+    /// Keep versions installed within this many days.
+    pub max_version_age_days: u32,
+    /// Remove auto-generated backups older than this many days.
+    pub auto_backup_max_age_days: u32,
+    /// Clear the pip cache, unless a pip install is running.
+    pub clear_pip_cache: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_latest_versions: 3,
+            max_version_age_days: 0,
+            auto_backup_max_age_days: 7,
+            clear_pip_cache: true,
+        }
+    }
+}
+
+/// An existing interpreter registered for a component slot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentProvider {
+    pub path: String,
+    /// Full version reported when the provider was registered.
+    pub version: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceConfig {
     pub name: String,
@@ -110,12 +223,76 @@ pub struct InstanceConfig {
     pub port: u16,
     #[serde(default)]
     pub created_at: String,
+    /// Component id overriding the version-derived Python (e.g. "python310").
+    #[serde(default)]
+    pub python_component: Option<String>,
+    /// Tool component ids whose executables are put on the instance's PATH.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Local source checkout a dev-mode instance runs from. Such instances
+    /// have an empty `version` and are never extracted from an archive.
+    #[serde(default)]
+    pub source_dir: Option<String>,
+    /// Keep a dev-mode instance's `data/` in the instance dir instead of the checkout.
+    #[serde(default)]
+    pub isolate_data: bool,
+    /// Which releases count as updates for this instance.
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Update unattended to the newest release of the channel, rolling back
+    /// if the updated instance fails to start.
+    #[serde(default)]
+    pub auto_update: bool,
+}
+
+/// Release channel an instance follows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Stable releases and prereleases.
+    Prerelease,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct InstalledVersion {
     pub version: String,
     pub zip_path: String,
+    /// `requires-python` declared by the source, read at install time.
+    #[serde(default)]
+    pub requires_python: Option<String>,
+    /// SHA-256 of the archive, recorded at install time.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Git ref the version was built from, `None` for official releases.
+    #[serde(default)]
+    pub source: Option<VersionSource>,
+    /// RFC 3339 time the version was first installed; reinstalls and branch
+    /// updates keep it. Missing for versions installed by older launchers.
+    #[serde(default)]
+    pub installed_at: Option<String>,
+}
+
+/// Kind of git ref a version was installed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefKind {
+    Tag,
+    Branch,
+    Commit,
+}
+
+/// Repository and ref a version was installed from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VersionSource {
+    /// Repository as "owner/repo".
+    pub repo: String,
+    pub kind: RefKind,
+    /// Tag, branch or commit SHA as requested.
+    pub ref_name: String,
+    /// Full commit SHA the ref resolved to at install time.
+    pub commit: String,
 }
 
 /// Backup metadata stored in backup.toml
@@ -142,6 +319,19 @@ pub struct BackupInfo {
     pub metadata: BackupMetadata,
 }
 
+/// Hand the config to `f` with all writes held off, then refuse any further
+/// writes if it succeeds. Used to move the config to another data root.
+pub fn with_config_frozen<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce(&AppConfig) -> Result<T>,
+{
+    let _guard = CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let config = load_config()?;
+    let result = f(&config)?;
+    CONFIG_FROZEN.store(true, Ordering::SeqCst);
+    Ok(result)
+}
+
 pub fn load_config() -> Result<Arc<AppConfig>> {
     let cache = get_config_cache()?;
     let config = cache.read().unwrap_or_else(|e| e.into_inner());
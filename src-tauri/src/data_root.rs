@@ -0,0 +1,346 @@
+//! Location of the launcher data directory, and moving it elsewhere.
+//!
+//! The root is resolved once at startup, in order of precedence:
+//! 1. the `--data-dir <path>` command-line flag,
+//! 2. the `ASTRBOT_LAUNCHER_DATA_DIR` environment variable,
+//! 3. a `portable` marker file next to the executable, which keeps data in
+//!    a `data/` directory beside it,
+//! 4. a pointer file written by a previous move,
+//! 5. `~/.astrbot_launcher`.
+//!
+//! The first three are chosen outside the launcher, so only the last two can
+//! be moved from within it.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::archive::copy_symlink;
+use crate::config::{with_config_frozen, AppConfig};
+use crate::error::{AppError, Result};
+use crate::preflight::ensure_free_space;
+
+const DATA_DIR_FLAG: &str = "--data-dir";
+const DATA_DIR_ENV: &str = "ASTRBOT_LAUNCHER_DATA_DIR";
+const PORTABLE_MARKER: &str = "portable";
+
+/// Where the data root was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataRootSource {
+    Flag,
+    Env,
+    Portable,
+    Pointer,
+    Default,
+}
+
+impl DataRootSource {
+    /// Whether the root was chosen outside the launcher and cannot be moved.
+    fn is_external(self) -> bool {
+        matches!(self, Self::Flag | Self::Env | Self::Portable)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataRoot {
+    pub path: PathBuf,
+    pub source: DataRootSource,
+}
+
+static DATA_ROOT: OnceLock<DataRoot> = OnceLock::new();
+
+/// The data root of this launcher process.
+pub fn data_root() -> &'static DataRoot {
+    DATA_ROOT.get_or_init(resolve_data_root)
+}
+
+fn resolve_data_root() -> DataRoot {
+    let root = |path: PathBuf, source| DataRoot { path, source };
+
+    if let Some(path) = flag_value(std::env::args_os().skip(1)) {
+        return root(path, DataRootSource::Flag);
+    }
+    if let Some(path) = std::env::var_os(DATA_DIR_ENV).filter(|value| !value.is_empty()) {
+        return root(PathBuf::from(path), DataRootSource::Env);
+    }
+    if let Some(exe_dir) = exe_dir() {
+        if exe_dir.join(PORTABLE_MARKER).is_file() {
+            return root(exe_dir.join("data"), DataRootSource::Portable);
+        }
+    }
+    if let Some(path) = pointer_file().and_then(|pointer| read_pointer(&pointer)) {
+        return root(path, DataRootSource::Pointer);
+    }
+    root(default_data_root(), DataRootSource::Default)
+}
+
+/// Value of `--data-dir <path>` or `--data-dir=<path>`.
+fn flag_value(mut args: impl Iterator<Item = OsString>) -> Option<PathBuf> {
+    while let Some(arg) = args.next() {
+        let Some(arg) = arg.to_str() else {
+            continue;
+        };
+        if arg == DATA_DIR_FLAG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg
+            .strip_prefix(DATA_DIR_FLAG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+/// `~/.astrbot_launcher`, or a `data/` directory next to the executable when
+/// there is no home directory.
+fn default_data_root() -> PathBuf {
+    if let Some(home) = dirs::home_dir() {
+        return home.join(".astrbot_launcher");
+    }
+    log::warn!("Cannot find home directory, keeping data next to the executable");
+    exe_dir().unwrap_or_default().join("data")
+}
+
+/// File recording a moved data root, kept outside any data root.
+fn pointer_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("astrbot-launcher").join("data-root"))
+}
+
+fn read_pointer(pointer: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(pointer).ok()?;
+    let path = PathBuf::from(content.trim());
+    if path.is_absolute() {
+        Some(path)
+    } else {
+        log::warn!(
+            "Ignoring data root pointer {:?}: {:?} is not absolute",
+            pointer,
+            path
+        );
+        None
+    }
+}
+
+/// Record `target` as the data root for the next start, or forget the
+/// pointer when `target` is the default location.
+fn write_pointer(target: &Path) -> Result<()> {
+    let pointer = pointer_file()
+        .ok_or_else(|| AppError::io("Cannot find a config directory for the data root pointer"))?;
+    if target == default_data_root() {
+        if pointer.exists() {
+            fs::remove_file(&pointer)
+                .map_err(|e| AppError::io(format!("Failed to remove {:?}: {}", pointer, e)))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = pointer.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::io(format!("Failed to create {:?}: {}", parent, e)))?;
+    }
+    let temp = pointer.with_extension("tmp");
+    fs::write(&temp, target.to_string_lossy().as_bytes())
+        .and_then(|()| fs::rename(&temp, &pointer))
+        .map_err(|e| AppError::io(format!("Failed to write {:?}: {}", pointer, e)))
+}
+
+/// Entries of the data root that are moved, which is all but instance venvs.
+///
+/// Venvs hardcode absolute interpreter paths, so moved copies would be
+/// broken. Without one an instance counts as undeployed and is rebuilt on
+/// its next start.
+fn movable_entries(root: &Path) -> impl Iterator<Item = walkdir::Result<DirEntry>> + '_ {
+    WalkDir::new(root).into_iter().filter_entry(move |entry| {
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            return false;
+        };
+        let mut components = relative.components().map(|c| c.as_os_str());
+        let is_venv = components.next().is_some_and(|c| c == "instances")
+            && components.next().is_some()
+            && components.next().is_some_and(|c| c == "venv")
+            && components.next().is_none();
+        !is_venv
+    })
+}
+
+/// Move the data root to `target` and switch to it from the next start.
+///
+/// Within one filesystem the root is renamed in place; its venvs come along
+/// and are rebuilt on the next start, as their deployment records the old
+/// data dir. Otherwise it is copied, and the old root is removed only once
+/// the copy is complete and the pointer to the new one written.
+///
+/// Config writes are held off during the move and refused after it, so
+/// nothing is lost or written to the old root. Callers make sure no instance
+/// is running and no download is active, then restart the launcher.
+pub fn move_data_root(target: &Path) -> Result<()> {
+    let current = data_root();
+    if current.source.is_external() {
+        return Err(AppError::config(format!(
+            "The data directory is set by {}, change it there instead",
+            match current.source {
+                DataRootSource::Flag => "the --data-dir flag",
+                DataRootSource::Env => DATA_DIR_ENV,
+                _ => "a portable marker file",
+            }
+        )));
+    }
+    validate_target(&current.path, target)?;
+    with_config_frozen(|config| relocate(config, &current.path, target))
+}
+
+fn relocate(config: &AppConfig, current: &Path, target: &Path) -> Result<()> {
+    log::info!("Moving data directory from {:?} to {:?}", current, target);
+    let existed = target.exists();
+    if existed {
+        // Validated to be empty; a rename needs it gone.
+        fs::remove_dir(target)
+            .map_err(|e| AppError::io(format!("Failed to remove {:?}: {}", target, e)))?;
+    }
+    let renamed = match fs::rename(current, target) {
+        Ok(()) => true,
+        Err(e) => {
+            log::info!("Cannot rename data directory ({}), copying instead", e);
+            false
+        }
+    };
+
+    let result = if renamed {
+        Ok(())
+    } else {
+        copy_data_root(current, target)
+    }
+    .and_then(|()| write_relocated_config(config, current, target))
+    .and_then(|()| write_pointer(target));
+    if let Err(e) = result {
+        if renamed {
+            if let Err(e) = fs::rename(target, current) {
+                log::error!("Failed to move data directory back to {:?}: {}", current, e);
+            }
+        } else {
+            let _ = fs::remove_dir_all(target);
+        }
+        if existed {
+            let _ = fs::create_dir(target);
+        }
+        return Err(e);
+    }
+
+    if renamed {
+        return Ok(());
+    }
+    if let Err(e) = fs::remove_dir_all(current) {
+        log::warn!("Failed to remove old data directory {:?}: {}", current, e);
+    }
+    Ok(())
+}
+
+fn validate_target(current: &Path, target: &Path) -> Result<()> {
+    if !target.is_absolute() {
+        return Err(AppError::config(format!(
+            "Data directory must be an absolute path: {:?}",
+            target
+        )));
+    }
+    // Compare resolved paths so links and `..` cannot hide an overlap.
+    let current = current
+        .canonicalize()
+        .unwrap_or_else(|_| current.to_path_buf());
+    let resolved = resolve_existing(target);
+    if resolved.starts_with(&current) || current.starts_with(&resolved) {
+        return Err(AppError::config(format!(
+            "Data directory cannot be moved into or above itself: {:?}",
+            target
+        )));
+    }
+    if target.exists() {
+        let empty = fs::read_dir(target)
+            .map_err(|e| AppError::io(format!("Failed to read {:?}: {}", target, e)))?
+            .next()
+            .is_none();
+        if !empty {
+            return Err(AppError::config(format!(
+                "Target directory is not empty: {:?}",
+                target
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Canonicalize the nearest existing ancestor of `path` and re-append the rest.
+fn resolve_existing(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return match path.strip_prefix(ancestor) {
+                Ok(rest) => canonical.join(rest),
+                Err(_) => canonical,
+            };
+        }
+    }
+    path.to_path_buf()
+}
+
+fn copy_data_root(from: &Path, to: &Path) -> Result<()> {
+    let required = movable_entries(from)
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    ensure_free_space(to, required)?;
+
+    for entry in movable_entries(from) {
+        let entry = entry.map_err(|e| AppError::io(e.to_string()))?;
+        let relative = entry
+            .path()
+            .strip_prefix(from)
+            .map_err(|e| AppError::io(e.to_string()))?;
+        let dest = to.join(relative);
+
+        // Runtime components ship relative links (e.g. Node's `bin/npm`),
+        // which have to stay links to keep resolving.
+        if entry.path_is_symlink() {
+            copy_symlink(entry.path(), &dest)?;
+        } else if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest)
+                .map_err(|e| AppError::io(format!("Failed to create {:?}: {}", dest, e)))?;
+        } else {
+            fs::copy(entry.path(), &dest)
+                .map_err(|e| AppError::io(format!("Failed to copy {:?}: {}", entry.path(), e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the config into the new root with paths under the old root rebased.
+fn write_relocated_config(config: &AppConfig, from: &Path, to: &Path) -> Result<()> {
+    let rebase = |path: &mut String| {
+        if let Ok(relative) = Path::new(path.as_str()).strip_prefix(from) {
+            *path = to.join(relative).to_string_lossy().into_owned();
+        }
+    };
+    let mut config = config.clone();
+    for version in &mut config.installed_versions {
+        rebase(&mut version.zip_path);
+    }
+    for provider in config.component_providers.values_mut() {
+        rebase(&mut provider.path);
+    }
+
+    let content = toml::to_string_pretty(&config).map_err(|e| AppError::config(e.to_string()))?;
+    fs::write(to.join("config.toml"), content).map_err(|e| AppError::config(e.to_string()))
+}
@@ -0,0 +1,268 @@
+//! Disk usage of the launcher data directory.
+//!
+//! Walking instance trees takes a while, so sizes are measured by a
+//! background scanner and cached. Snapshots read the cache; each entry is
+//! replaced as soon as it has been re-measured, so a scan in progress shows
+//! fresh sizes for what it has reached and the previous ones for the rest.
+
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+use walkdir::WalkDir;
+
+use crate::backup::list_backups;
+use crate::config::load_config;
+use crate::instance::instance_data_dir;
+use crate::paths::{
+    get_components_dir, get_data_dir, get_instance_dir, get_instance_venv_dir, get_versions_dir,
+    get_wheelhouses_dir,
+};
+
+/// Size of a named entry: a version archive, backup or component.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntrySize {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Size of an instance's `data/` directory, split by what AstrBot keeps there.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataUsage {
+    pub plugins: u64,
+    pub logs: u64,
+    pub temp: u64,
+    pub knowledge_base: u64,
+    /// Everything else, including config and databases.
+    pub other: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceDiskUsage {
+    pub id: String,
+    /// Extracted code without `data/`; 0 for dev-mode instances, whose
+    /// checkout lives outside the launcher directory.
+    pub core: u64,
+    pub venv: u64,
+    pub data: DataUsage,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiskUsage {
+    pub instances: Vec<InstanceDiskUsage>,
+    pub versions: Vec<EntrySize>,
+    pub backups: Vec<EntrySize>,
+    pub components: Vec<EntrySize>,
+    pub wheelhouses: u64,
+    /// GitHub response and pip caches.
+    pub cache: u64,
+    pub total: u64,
+    pub scanning: bool,
+    /// RFC 3339 time the last complete scan finished.
+    pub scanned_at: Option<String>,
+}
+
+static USAGE: OnceLock<RwLock<DiskUsage>> = OnceLock::new();
+static SCAN_REQUESTED: OnceLock<Notify> = OnceLock::new();
+
+fn usage() -> &'static RwLock<DiskUsage> {
+    USAGE.get_or_init(|| RwLock::new(DiskUsage::default()))
+}
+
+fn scan_requested() -> &'static Notify {
+    SCAN_REQUESTED.get_or_init(Notify::new)
+}
+
+fn update(f: impl FnOnce(&mut DiskUsage)) {
+    let mut usage = usage().write().unwrap_or_else(|e| e.into_inner());
+    f(&mut usage);
+    usage.total = total(&usage);
+}
+
+/// The cached disk usage, as of the last scan.
+pub fn disk_usage() -> DiskUsage {
+    usage().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Ask the background scanner to rescan now instead of at the next interval.
+pub fn request_scan() {
+    scan_requested().notify_one();
+}
+
+/// Wait until the next scan is due or requested.
+pub async fn wait_for_next_scan(interval: Duration) {
+    tokio::select! {
+        () = tokio::time::sleep(interval) => {}
+        () = scan_requested().notified() => {}
+    }
+}
+
+/// Measure everything once, updating the cache entry by entry.
+pub async fn scan() {
+    update(|usage| usage.scanning = true);
+    if let Err(e) = tokio::task::spawn_blocking(scan_blocking).await {
+        log::warn!("Disk usage scan failed: {}", e);
+    }
+    update(|usage| usage.scanning = false);
+}
+
+fn scan_blocking() {
+    let instance_ids: Vec<(String, bool)> = load_config()
+        .map(|config| {
+            config
+                .instances
+                .iter()
+                .map(|(id, instance)| (id.clone(), instance.source_dir.is_some()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    update(|usage| {
+        usage
+            .instances
+            .retain(|i| instance_ids.iter().any(|(id, _)| *id == i.id));
+    });
+    for (id, is_dev) in &instance_ids {
+        let measured = measure_instance(id, *is_dev);
+        update(
+            |usage| match usage.instances.iter_mut().find(|i| i.id == *id) {
+                Some(existing) => *existing = measured,
+                None => usage.instances.push(measured),
+            },
+        );
+    }
+
+    let versions = measure_entries(&get_versions_dir(), |name| name.ends_with(".zip"));
+    update(|usage| usage.versions = versions);
+
+    let backups = list_backups()
+        .map(|backups| {
+            backups
+                .into_iter()
+                .map(|backup| EntrySize {
+                    size: disk_size(Path::new(&backup.path)),
+                    name: backup.filename,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    update(|usage| usage.backups = backups);
+
+    let components = measure_entries(&get_components_dir(), |_| true);
+    update(|usage| usage.components = components);
+
+    let wheelhouses = disk_size(&get_wheelhouses_dir());
+    let cache = disk_size(&get_data_dir().join("cache"));
+    update(|usage| {
+        usage.wheelhouses = wheelhouses;
+        usage.cache = cache;
+        usage.scanned_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+}
+
+fn measure_instance(instance_id: &str, is_dev: bool) -> InstanceDiskUsage {
+    // `data/` is measured on its own, wherever the instance keeps it.
+    let core = if is_dev {
+        0
+    } else {
+        disk_size_excluding(&get_instance_dir(instance_id).join("core"), "data")
+    };
+    let venv = disk_size(&get_instance_venv_dir(instance_id));
+    let data = measure_data(&instance_data_dir(instance_id));
+
+    InstanceDiskUsage {
+        id: instance_id.to_string(),
+        core,
+        venv,
+        total: core + venv + data.total,
+        data,
+    }
+}
+
+/// Measure `data/` in one walk, bucketing files by their top-level entry.
+fn measure_data(data_dir: &Path) -> DataUsage {
+    let mut usage = DataUsage::default();
+    let files = WalkDir::new(data_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file());
+    for entry in files {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let size = metadata.len();
+        let top = entry
+            .path()
+            .strip_prefix(data_dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map(|component| component.as_os_str());
+        let bucket = match top.and_then(|name| name.to_str()) {
+            Some("plugins") => &mut usage.plugins,
+            Some("logs") => &mut usage.logs,
+            Some("temp") => &mut usage.temp,
+            Some("knowledge_base") => &mut usage.knowledge_base,
+            _ => &mut usage.other,
+        };
+        *bucket += size;
+        usage.total += size;
+    }
+    usage
+}
+
+/// Sizes of the entries of `dir` whose names pass `filter`, largest first.
+fn measure_entries(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<EntrySize> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sizes: Vec<EntrySize> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            filter(&name).then(|| EntrySize {
+                size: disk_size(&entry.path()),
+                name,
+            })
+        })
+        .collect();
+    sizes.sort_by_key(|entry| std::cmp::Reverse(entry.size));
+    sizes
+}
+
+fn total(usage: &DiskUsage) -> u64 {
+    let instances: u64 = usage.instances.iter().map(|i| i.total).sum();
+    let entries = |sizes: &[EntrySize]| sizes.iter().map(|e| e.size).sum::<u64>();
+    instances
+        + entries(&usage.versions)
+        + entries(&usage.backups)
+        + entries(&usage.components)
+        + usage.wheelhouses
+        + usage.cache
+}
+
+/// Size of a file, or the total size of the files under a directory.
+pub fn disk_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Size of a directory, leaving out its direct child named `skip`.
+fn disk_size_excluding(dir: &Path, skip: &str) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| !(entry.depth() == 1 && entry.file_name() == skip))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
@@ -0,0 +1,256 @@
+//! Download queue with a concurrency limit, progress reporting and cancellation.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::{broadcast, watch, Semaphore, SemaphorePermit};
+
+use crate::error::{AppError, Result};
+
+/// Downloads transferring at the same time; others wait in the queue.
+const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+/// Minimum time between two progress events of the same download.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+static DOWNLOAD_MANAGER: OnceLock<DownloadManager> = OnceLock::new();
+
+/// Lifecycle state of a download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+    Queued,
+    Downloading,
+    /// Waiting to retry after a transient failure.
+    Retrying,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Progress of a single download, sent to the frontend as `download-progress`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    /// Caller-chosen id, e.g. "version:v4.14.8" or "component:python312".
+    pub id: String,
+    pub url: String,
+    pub state: DownloadState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// Bytes per second over the current attempt.
+    pub speed: u64,
+    /// Estimated seconds remaining, when the total size is known.
+    pub eta_secs: Option<u64>,
+}
+
+struct ActiveDownload {
+    progress: DownloadProgress,
+    cancel: watch::Sender<bool>,
+}
+
+/// Tracks queued and running downloads.
+pub struct DownloadManager {
+    slots: Semaphore,
+    downloads: RwLock<HashMap<String, ActiveDownload>>,
+    events: broadcast::Sender<DownloadProgress>,
+}
+
+/// The process-wide download manager.
+pub fn download_manager() -> &'static DownloadManager {
+    DOWNLOAD_MANAGER.get_or_init(DownloadManager::new)
+}
+
+impl DownloadManager {
+    fn new() -> Self {
+        let (events, _) = broadcast::channel(128);
+        Self {
+            slots: Semaphore::new(MAX_CONCURRENT_DOWNLOADS),
+            downloads: RwLock::new(HashMap::new()),
+            events,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DownloadProgress> {
+        self.events.subscribe()
+    }
+
+    /// Progress of all queued and running downloads.
+    pub fn list(&self) -> Vec<DownloadProgress> {
+        let downloads = self.downloads.read().unwrap_or_else(|e| e.into_inner());
+        downloads.values().map(|d| d.progress.clone()).collect()
+    }
+
+    /// Whether any download is queued or in progress.
+    pub fn has_active(&self) -> bool {
+        let downloads = self.downloads.read().unwrap_or_else(|e| e.into_inner());
+        downloads.values().any(|d| {
+            matches!(
+                d.progress.state,
+                DownloadState::Queued | DownloadState::Downloading | DownloadState::Retrying
+            )
+        })
+    }
+
+    /// Request cancellation of a download. Returns `false` if it is not active.
+    pub fn cancel(&self, id: &str) -> bool {
+        let downloads = self.downloads.read().unwrap_or_else(|e| e.into_inner());
+        match downloads.get(id) {
+            Some(download) => {
+                log::info!("Cancelling download {}", id);
+                download.cancel.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Register a new download in the queue.
+    pub(super) fn register(&'static self, id: &str, url: &str) -> Result<DownloadTask> {
+        let progress = DownloadProgress {
+            id: id.to_string(),
+            url: url.to_string(),
+            state: DownloadState::Queued,
+            downloaded: 0,
+            total: None,
+            speed: 0,
+            eta_secs: None,
+        };
+        let (cancel, cancel_rx) = watch::channel(false);
+
+        {
+            let mut downloads = self.downloads.write().unwrap_or_else(|e| e.into_inner());
+            if downloads.contains_key(id) {
+                return Err(AppError::other(format!(
+                    "{} is already being downloaded",
+                    id
+                )));
+            }
+            downloads.insert(
+                id.to_string(),
+                ActiveDownload {
+                    progress: progress.clone(),
+                    cancel,
+                },
+            );
+        }
+        let _ = self.events.send(progress);
+
+        Ok(DownloadTask {
+            manager: self,
+            id: id.to_string(),
+            cancel: cancel_rx,
+            attempt_start: Instant::now(),
+            attempt_offset: 0,
+            total: None,
+            last_emit: None,
+        })
+    }
+
+    fn update(&self, id: &str, apply: impl FnOnce(&mut DownloadProgress)) {
+        let mut downloads = self.downloads.write().unwrap_or_else(|e| e.into_inner());
+        let Some(download) = downloads.get_mut(id) else {
+            return;
+        };
+        apply(&mut download.progress);
+        let progress = download.progress.clone();
+        drop(downloads);
+        let _ = self.events.send(progress);
+    }
+}
+
+/// A registered download. Removed from the manager when dropped.
+pub(super) struct DownloadTask {
+    manager: &'static DownloadManager,
+    id: String,
+    cancel: watch::Receiver<bool>,
+    attempt_start: Instant,
+    attempt_offset: u64,
+    total: Option<u64>,
+    last_emit: Option<Instant>,
+}
+
+impl DownloadTask {
+    /// Wait for a free download slot, or `None` if cancelled while queued.
+    pub async fn acquire_slot(&mut self) -> Option<SemaphorePermit<'static>> {
+        let manager = self.manager;
+        tokio::select! {
+            permit = manager.slots.acquire() => permit.ok(),
+            () = self.cancelled() => None,
+        }
+    }
+
+    /// Resolves once cancellation has been requested.
+    pub async fn cancelled(&mut self) {
+        if self.cancel.wait_for(|cancelled| *cancelled).await.is_err() {
+            // The sender lives as long as the task is registered.
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Start (or resume) transferring at `offset` bytes of `total`.
+    pub fn start_attempt(&mut self, offset: u64, total: Option<u64>) {
+        self.attempt_start = Instant::now();
+        self.attempt_offset = offset;
+        self.total = total;
+        self.last_emit = None;
+        self.manager.update(&self.id, |progress| {
+            progress.state = DownloadState::Downloading;
+            progress.downloaded = offset;
+            progress.total = total;
+            progress.speed = 0;
+            progress.eta_secs = None;
+        });
+    }
+
+    /// Report bytes written so far; events are throttled.
+    pub fn report(&mut self, downloaded: u64) {
+        let now = Instant::now();
+        if self
+            .last_emit
+            .is_some_and(|last| now.duration_since(last) < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(now);
+
+        let elapsed = now.duration_since(self.attempt_start).as_secs_f64();
+        let transferred = downloaded.saturating_sub(self.attempt_offset);
+        let speed = if elapsed > 0.0 {
+            (transferred as f64 / elapsed) as u64
+        } else {
+            0
+        };
+        let eta_secs = match (self.total, speed) {
+            (Some(total), speed) if speed > 0 => Some(total.saturating_sub(downloaded) / speed),
+            _ => None,
+        };
+
+        self.manager.update(&self.id, |progress| {
+            progress.downloaded = downloaded;
+            progress.speed = speed;
+            progress.eta_secs = eta_secs;
+        });
+    }
+
+    pub fn set_state(&self, state: DownloadState) {
+        self.manager.update(&self.id, |progress| {
+            progress.state = state;
+            if state != DownloadState::Downloading {
+                progress.speed = 0;
+                progress.eta_secs = None;
+            }
+        });
+    }
+}
+
+impl Drop for DownloadTask {
+    fn drop(&mut self) {
+        let mut downloads = self
+            .manager
+            .downloads
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        downloads.remove(&self.id);
+    }
+}
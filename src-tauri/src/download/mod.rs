@@ -0,0 +1,687 @@
+mod manager;
+
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures_util::StreamExt as _;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+
+use crate::archive::read_zip_root_file;
+use crate::checksum::sha256_file;
+use crate::component::read_requires_python_from_zip;
+use crate::config::{load_config, with_config_mut, InstalledVersion, RefKind, VersionSource};
+use crate::error::{AppError, Result};
+use crate::github::{
+    build_archive_url, build_download_url, resolve_commit, GitHubRelease, ASTRBOT_REPO,
+};
+use crate::instance::invalidate_version_deployments;
+use crate::mirror::{with_mirror_fallback, MirrorKind};
+use crate::paths::get_versions_dir;
+use crate::preflight::ensure_free_space;
+use crate::validation::resolve_version_zip_path;
+
+use manager::DownloadTask;
+pub use manager::{download_manager, DownloadProgress, DownloadState};
+
+pub(crate) const USER_AGENT: &str = "astrbot-launcher";
+
+/// Attempts per download before giving up on transient errors.
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 5;
+/// Delay before the first retry, doubled for each following one.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Why a download attempt failed.
+enum AttemptError {
+    /// Worth retrying: connection drops, timeouts, 5xx and 429 responses.
+    Transient(AppError),
+    Fatal(AppError),
+    Cancelled,
+}
+
+/// Download a file from `url` and stream it to `dest`.
+///
+/// The download is queued in the download manager under `download_id` and
+/// reports its progress there until it completes, fails or is cancelled.
+/// Data is written to `<dest>.part` and resumed with an HTTP Range request
+/// after transient failures, when the server supports it. Resuming is
+/// conditional on the ETag or Last-Modified the part was started with, kept
+/// in `<dest>.part.validator`, so a changed resource is downloaded afresh.
+/// The file is only renamed into place once its length matches what the
+/// server announced; downloads of unknown length are never resumed.
+pub async fn download_file(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    download_id: &str,
+) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(e.to_string()))?;
+    }
+
+    let part_path = part_path(dest);
+
+    let mut task = download_manager().register(download_id, url)?;
+    let result = match task.acquire_slot().await {
+        Some(_permit) => download_with_retries(client, url, &part_path, &mut task).await,
+        None => Err(AttemptError::Cancelled),
+    };
+
+    let result = match result {
+        Ok(()) => {
+            let _ = fs::remove_file(validator_path(&part_path));
+            fs::rename(&part_path, dest).map_err(|e| {
+                AppError::io(format!("Failed to move {:?} into place: {}", part_path, e))
+            })
+        }
+        Err(AttemptError::Cancelled) => {
+            log::info!("Download of {} cancelled", url);
+            if part_path.exists() {
+                remove_part_file(&part_path);
+            }
+            task.set_state(DownloadState::Cancelled);
+            return Err(AppError::download_cancelled(url));
+        }
+        Err(AttemptError::Transient(e) | AttemptError::Fatal(e)) => Err(e),
+    };
+
+    task.set_state(if result.is_ok() {
+        DownloadState::Completed
+    } else {
+        DownloadState::Failed
+    });
+    result
+}
+
+/// Run download attempts, backing off between transient failures.
+async fn download_with_retries(
+    client: &Client,
+    url: &str,
+    part_path: &Path,
+    task: &mut DownloadTask,
+) -> std::result::Result<(), AttemptError> {
+    let mut attempt = 1;
+    loop {
+        match download_attempt(client, url, part_path, task).await {
+            Ok(()) => return Ok(()),
+            Err(AttemptError::Transient(e)) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                log::warn!(
+                    "Download of {} failed (attempt {}/{}), retrying in {:?}: {}",
+                    url,
+                    attempt,
+                    MAX_DOWNLOAD_ATTEMPTS,
+                    delay,
+                    e
+                );
+                task.set_state(DownloadState::Retrying);
+                tokio::select! {
+                    () = tokio::time::sleep(delay) => {}
+                    () = task.cancelled() => return Err(AttemptError::Cancelled),
+                }
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Download into `part_path`, continuing after any data already in it.
+async fn download_attempt(
+    client: &Client,
+    url: &str,
+    part_path: &Path,
+    task: &mut DownloadTask,
+) -> std::result::Result<(), AttemptError> {
+    let mut existing = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    let validator = fs::read_to_string(validator_path(part_path)).ok();
+    if existing > 0 && validator.is_none() {
+        // Nothing proves the part belongs to the current resource.
+        remove_part_file(part_path);
+        existing = 0;
+    }
+
+    let mut request = client.get(url).header("User-Agent", USER_AGENT);
+    if let Some(validator) = validator.as_deref().filter(|_| existing > 0) {
+        request = request
+            .header(RANGE, format!("bytes={}-", existing))
+            .header(IF_RANGE, validator);
+    }
+
+    let resp = tokio::select! {
+        resp = request.send() => resp,
+        () = task.cancelled() => return Err(AttemptError::Cancelled),
+    }
+    .map_err(|e| AttemptError::Transient(AppError::network_with_url(url, e.to_string())))?;
+
+    let status = resp.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file does not fit the remote one; start over.
+        remove_part_file(part_path);
+        return Err(AttemptError::Transient(AppError::network_with_url(
+            url,
+            status.to_string(),
+        )));
+    }
+    if !status.is_success() {
+        let error = AppError::network_with_url(url, status.to_string());
+        return Err(
+            if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                AttemptError::Transient(error)
+            } else {
+                AttemptError::Fatal(error)
+            },
+        );
+    }
+
+    // Servers without Range support, and any server whose resource changed
+    // since the part was started, answer 200 with the whole file.
+    let resumed = status == StatusCode::PARTIAL_CONTENT;
+    if resumed && !content_range_starts_at(&resp, existing) {
+        remove_part_file(part_path);
+        return Err(AttemptError::Transient(AppError::network_with_url(
+            url,
+            "unexpected Content-Range",
+        )));
+    }
+    let offset = if resumed { existing } else { 0 };
+    let expected_len = if resumed {
+        content_range_total(&resp).or_else(|| resp.content_length().map(|len| len + offset))
+    } else {
+        resp.content_length()
+    };
+    if !resumed {
+        // Only a download of known length with a validator may be resumed.
+        let validator = expected_len.and_then(|_| response_validator(&resp));
+        write_validator(part_path, validator.as_deref())
+            .map_err(|e| AttemptError::Fatal(AppError::io(e.to_string())))?;
+    }
+    if let Some(remaining) = resp.content_length() {
+        ensure_free_space(part_path, remaining).map_err(AttemptError::Fatal)?;
+    }
+
+    let file = if resumed {
+        fs::OpenOptions::new().append(true).open(part_path)
+    } else {
+        fs::File::create(part_path)
+    };
+    let mut file = file.map_err(|e| AttemptError::Fatal(AppError::io(e.to_string())))?;
+
+    task.start_attempt(offset, expected_len);
+
+    let mut written = offset;
+    let mut stream = resp.bytes_stream();
+    loop {
+        let next = tokio::select! {
+            next = stream.next() => next,
+            () = task.cancelled() => return Err(AttemptError::Cancelled),
+        };
+        let Some(chunk) = next else {
+            break;
+        };
+        let chunk = chunk
+            .map_err(|e| AttemptError::Transient(AppError::network_with_url(url, e.to_string())))?;
+        file.write_all(&chunk)
+            .map_err(|e| AttemptError::Fatal(AppError::io(e.to_string())))?;
+        written += chunk.len() as u64;
+        task.report(written);
+    }
+    file.flush()
+        .map_err(|e| AttemptError::Fatal(AppError::io(e.to_string())))?;
+
+    if let Some(expected) = expected_len {
+        if written != expected {
+            return Err(AttemptError::Transient(AppError::network_with_url(
+                url,
+                format!("incomplete download: {} of {} bytes", written, expected),
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a 206 response continues at `offset` (`Content-Range: bytes <offset>-...`).
+fn content_range_starts_at(resp: &reqwest::Response, offset: u64) -> bool {
+    resp.headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("bytes "))
+        .and_then(|range| range.split('-').next())
+        .and_then(|start| start.trim().parse::<u64>().ok())
+        == Some(offset)
+}
+
+/// Total length from a 206 response's `Content-Range: bytes <range>/<total>`.
+fn content_range_total(resp: &reqwest::Response) -> Option<u64> {
+    resp.headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit_once('/'))
+        .and_then(|(_, total)| total.trim().parse::<u64>().ok())
+}
+
+/// Strong ETag or else Last-Modified of a response, usable in `If-Range`.
+fn response_validator(resp: &reqwest::Response) -> Option<String> {
+    let header = |name| {
+        resp.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    // Weak ETags are not allowed in If-Range.
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+}
+
+/// Record the validator of a new part file, or remove a stale one.
+fn write_validator(part_path: &Path, validator: Option<&str>) -> std::io::Result<()> {
+    let path = validator_path(part_path);
+    match validator {
+        Some(validator) => fs::write(path, validator),
+        None if path.exists() => fs::remove_file(path),
+        None => Ok(()),
+    }
+}
+
+fn validator_path(part_path: &Path) -> PathBuf {
+    let mut name = part_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".validator");
+    part_path.with_file_name(name)
+}
+
+/// Where `download_file` keeps the data of an unfinished download of `dest`.
+fn part_path(dest: &Path) -> PathBuf {
+    let mut part_name = dest.file_name().unwrap_or_default().to_os_string();
+    part_name.push(".part");
+    dest.with_file_name(part_name)
+}
+
+fn remove_part_file(part_path: &Path) {
+    if let Err(e) = fs::remove_file(part_path) {
+        log::warn!("Failed to remove partial download {:?}: {}", part_path, e);
+    }
+    let _ = fs::remove_file(validator_path(part_path));
+}
+
+/// Fetch a text document from `url`.
+pub async fn fetch_text(client: &Client, url: &str) -> Result<String> {
+    let resp = client
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| AppError::network_with_url(url, e.to_string()))?;
+
+    if !resp.status().is_success() {
+        return Err(AppError::network_with_url(url, resp.status().to_string()));
+    }
+
+    resp.text()
+        .await
+        .map_err(|e| AppError::network_with_url(url, e.to_string()))
+}
+
+/// Check whether `url` is reachable (HTTP GET returns a success status).
+pub async fn check_url(client: &Client, url: &str) -> Result<()> {
+    let resp = client
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| AppError::network_with_url(url, e.to_string()))?;
+
+    if !resp.status().is_success() {
+        return Err(AppError::network_with_url(url, resp.status().to_string()));
+    }
+
+    Ok(())
+}
+
+/// Download and register an AstrBot version archive.
+pub async fn download_version(client: &Client, release: &GitHubRelease) -> Result<()> {
+    let version = &release.tag_name;
+    let versions_dir = get_versions_dir();
+    let zip_path = resolve_version_zip_path(version)?;
+
+    std::fs::create_dir_all(&versions_dir)
+        .map_err(|e| AppError::io(format!("Failed to create versions dir: {}", e)))?;
+
+    if zip_path.exists() {
+        if let Err(e) = std::fs::remove_file(&zip_path) {
+            log::warn!("Failed to remove old zip {:?}: {}", zip_path, e);
+        }
+    }
+
+    let download_id = format!("version:{}", version);
+    with_mirror_fallback(MirrorKind::GitHub, |proxy| {
+        let url = build_download_url(&proxy, version);
+        let (zip_path, download_id) = (&zip_path, &download_id);
+        async move { download_file(client, &url, zip_path, download_id).await }
+    })
+    .await?;
+
+    register_version(version, &zip_path, None)
+}
+
+/// Install a version from a tag, branch or commit of a source repository.
+///
+/// The ref is resolved to a commit first and that exact commit is downloaded,
+/// so the recorded source always matches the archive. Returns the version name,
+/// derived from the ref unless given explicitly.
+pub async fn install_version_from_ref(
+    client: &Client,
+    repo: &str,
+    kind: RefKind,
+    ref_name: &str,
+    version: Option<&str>,
+) -> Result<String> {
+    let repo = repo.trim();
+    let ref_name = ref_name.trim();
+    if repo != ASTRBOT_REPO {
+        let config = load_config()?;
+        if !config.source_repositories.iter().any(|r| r == repo) {
+            return Err(AppError::config(format!(
+                "Source repository {} is not registered",
+                repo
+            )));
+        }
+    }
+    if ref_name.is_empty() {
+        return Err(AppError::other("Ref must not be empty"));
+    }
+
+    let commit = resolve_commit(client, repo, ref_name).await?;
+    let version = match version.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(version) => version.to_string(),
+        None => ref_version_name(repo, kind, ref_name, &commit),
+    };
+    // Replacing an installed archive would pull it from under its instances;
+    // branch versions are refreshed through `update_ref_version` instead.
+    if load_config()?
+        .installed_versions
+        .iter()
+        .any(|v| v.version == version)
+    {
+        return Err(AppError::other(format!(
+            "Version {} is already installed, choose another name or update it",
+            version
+        )));
+    }
+    let zip_path = resolve_version_zip_path(&version)?;
+
+    download_commit(client, repo, &commit, &version, &zip_path).await?;
+    let source = VersionSource {
+        repo: repo.to_string(),
+        kind,
+        ref_name: ref_name.to_string(),
+        commit,
+    };
+    log::info!(
+        "Installed version {} from {}@{} ({})",
+        version,
+        source.repo,
+        source.ref_name,
+        source.commit
+    );
+    register_version(&version, &zip_path, Some(source))?;
+    Ok(version)
+}
+
+/// Pull the latest commit of a branch-based version.
+///
+/// The archive is replaced in place and instances on the version re-extract
+/// it on their next start. Returns a message for the user.
+pub async fn update_ref_version(client: &Client, version: &str) -> Result<String> {
+    let config = load_config()?;
+    let installed = config
+        .installed_versions
+        .iter()
+        .find(|v| v.version == version)
+        .ok_or_else(|| AppError::version_not_found(version))?;
+    let Some(source) = installed
+        .source
+        .clone()
+        .filter(|source| source.kind == RefKind::Branch)
+    else {
+        return Err(AppError::other(format!(
+            "Version {} is not installed from a branch",
+            version
+        )));
+    };
+    let zip_path = resolve_version_zip_path(version)?;
+
+    let commit = resolve_commit(client, &source.repo, &source.ref_name).await?;
+    if commit == source.commit {
+        return Ok(format!("{} 已是最新提交 {}", version, short_sha(&commit)));
+    }
+
+    // The download replaces the archive only once it is complete.
+    download_commit(client, &source.repo, &commit, version, &zip_path).await?;
+    let previous = source.commit.clone();
+    register_version(
+        version,
+        &zip_path,
+        Some(VersionSource {
+            commit: commit.clone(),
+            ..source
+        }),
+    )?;
+    let redeployed = invalidate_version_deployments(version)?;
+    log::info!(
+        "Updated version {} from {} to {}, {} instance(s) will redeploy",
+        version,
+        previous,
+        commit,
+        redeployed
+    );
+
+    Ok(format!(
+        "已更新 {}: {} -> {}，{} 个实例将在下次启动时重新部署",
+        version,
+        short_sha(&previous),
+        short_sha(&commit),
+        redeployed
+    ))
+}
+
+/// Download the source archive of a commit to `zip_path`.
+async fn download_commit(
+    client: &Client,
+    repo: &str,
+    commit: &str,
+    version: &str,
+    zip_path: &Path,
+) -> Result<()> {
+    std::fs::create_dir_all(get_versions_dir())
+        .map_err(|e| AppError::io(format!("Failed to create versions dir: {}", e)))?;
+
+    // A part left by another commit of the same version must not be resumed.
+    let part_path = part_path(zip_path);
+    if part_path.exists() {
+        remove_part_file(&part_path);
+    }
+
+    let download_id = format!("version:{}", version);
+    with_mirror_fallback(MirrorKind::GitHub, |proxy| {
+        let url = build_archive_url(&proxy, repo, commit);
+        let download_id = &download_id;
+        async move { download_file(client, &url, zip_path, download_id).await }
+    })
+    .await
+}
+
+fn short_sha(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
+
+/// Version name for a ref install: the ref itself, or the short SHA for
+/// commits, suffixed with `+owner` for repositories other than the official one.
+fn ref_version_name(repo: &str, kind: RefKind, ref_name: &str, commit: &str) -> String {
+    let base = match kind {
+        RefKind::Commit => short_sha(commit),
+        RefKind::Tag | RefKind::Branch => ref_name,
+    };
+    let mut name = sanitize_version_name(base);
+    if repo != ASTRBOT_REPO {
+        let owner = repo.split('/').next().unwrap_or(repo);
+        name.push('+');
+        name.push_str(&sanitize_version_name(owner));
+    }
+    name
+}
+
+/// Replace characters not allowed in version names (e.g. `/` in branches).
+fn sanitize_version_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Import an AstrBot source zip from a local file.
+///
+/// The version tag is taken from `[project].version` in the archive's
+/// pyproject.toml unless given explicitly.
+pub fn import_version(archive_path: &Path, version: Option<&str>) -> Result<String> {
+    if read_zip_root_file(archive_path, "main.py")?.is_none() {
+        return Err(AppError::other(format!(
+            "{:?} is not an AstrBot source archive: main.py not found",
+            archive_path
+        )));
+    }
+
+    let version = match version.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(version) => version.to_string(),
+        None => read_source_version(archive_path)?,
+    };
+    // Replacing an installed archive would pull it from under its instances.
+    if load_config()?
+        .installed_versions
+        .iter()
+        .any(|v| v.version == version)
+    {
+        return Err(AppError::other(format!(
+            "Version {} is already installed, choose another name or remove it first",
+            version
+        )));
+    }
+    let zip_path = resolve_version_zip_path(&version)?;
+
+    // Copying a file onto itself would truncate it.
+    let same_file =
+        archive_path.canonicalize().ok() == zip_path.canonicalize().ok() && zip_path.exists();
+    if !same_file {
+        std::fs::copy(archive_path, &zip_path)
+            .map_err(|e| AppError::io(format!("Failed to copy {:?}: {}", archive_path, e)))?;
+    }
+
+    register_version(&version, &zip_path, None)?;
+    log::info!("Imported version {} from {:?}", version, archive_path);
+    Ok(version)
+}
+
+/// Version tag of a source archive, from `[project].version` in pyproject.toml.
+fn read_source_version(archive_path: &Path) -> Result<String> {
+    #[derive(Deserialize)]
+    struct Pyproject {
+        project: Option<PyprojectProject>,
+    }
+
+    #[derive(Deserialize)]
+    struct PyprojectProject {
+        version: Option<String>,
+    }
+
+    let content = read_zip_root_file(archive_path, "pyproject.toml")?
+        .ok_or_else(|| AppError::other("Archive has no pyproject.toml, specify the version"))?;
+    let pyproject: Pyproject = toml::from_str(&content)
+        .map_err(|e| AppError::other(format!("Failed to parse pyproject.toml: {}", e)))?;
+
+    pyproject
+        .project
+        .and_then(|project| project.version)
+        .map(|version| format!("v{}", version.trim().trim_start_matches('v')))
+        .ok_or_else(|| AppError::other("pyproject.toml has no version, specify the version"))
+}
+
+/// Record a version archive in `versions/` as installed, with the git ref it
+/// was built from if any.
+pub(crate) fn register_version(
+    version: &str,
+    zip_path: &Path,
+    source: Option<VersionSource>,
+) -> Result<()> {
+    // Source archives have no published sums; record one now so later
+    // deployments can detect a corrupted or replaced archive.
+    let sha256 = sha256_file(zip_path)?;
+    let requires_python = read_requires_python_from_zip(zip_path);
+    if requires_python.is_none() {
+        log::info!(
+            "No requires-python found for {}, using built-in table",
+            version
+        );
+    }
+
+    let mut installed = InstalledVersion {
+        version: version.to_string(),
+        zip_path: zip_path.to_str().unwrap_or("").to_string(),
+        requires_python,
+        sha256: Some(sha256),
+        source,
+        installed_at: Some(chrono::Utc::now().to_rfc3339()),
+    };
+
+    with_config_mut(move |config| {
+        if let Some(index) = config
+            .installed_versions
+            .iter()
+            .position(|v| v.version == installed.version)
+        {
+            let previous = config.installed_versions.remove(index);
+            if previous.installed_at.is_some() {
+                installed.installed_at = previous.installed_at;
+            }
+        }
+        config.installed_versions.push(installed);
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Unregister and remove an AstrBot version archive.
+pub fn remove_version(version: &str) -> Result<()> {
+    let zip_path = resolve_version_zip_path(version)?;
+
+    let version_owned = version.to_string();
+    with_config_mut(|config| {
+        for inst in config.instances.values() {
+            if inst.version == version_owned.as_str() {
+                return Err(AppError::version_in_use(&version_owned, &inst.name));
+            }
+        }
+
+        config
+            .installed_versions
+            .retain(|v| v.version != version_owned.as_str());
+        Ok(())
+    })?;
+
+    if zip_path.exists() {
+        if let Err(e) = std::fs::remove_file(&zip_path) {
+            log::warn!("Failed to remove zip {:?}: {}", zip_path, e);
+        }
+    }
+
+    Ok(())
+}
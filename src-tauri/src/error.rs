@@ -31,6 +31,12 @@ pub enum ErrorKind {
     Io,
     /// Network error
     Network,
+    /// Downloaded or stored file does not match its SHA-256 sum
+    ChecksumMismatch,
+    /// Download was cancelled by the user
+    DownloadCancelled,
+    /// Not enough free space for an operation
+    InsufficientDiskSpace,
     /// Python runtime error
     Python,
     /// Python is not installed
@@ -45,6 +51,8 @@ pub enum ErrorKind {
     Backup,
     /// GitHub API error
     GitHub,
+    /// GitHub API rate limit exhausted
+    RateLimited,
     /// General error
     Other,
 }
@@ -60,6 +68,9 @@ impl ErrorKind {
             Self::Config => 2001,
             Self::Io => 2002,
             Self::Network => 2003,
+            Self::ChecksumMismatch => 2004,
+            Self::DownloadCancelled => 2005,
+            Self::InsufficientDiskSpace => 2006,
             Self::Python => 3001,
             Self::PythonNotInstalled => 3002,
             Self::Process => 3003,
@@ -67,6 +78,7 @@ impl ErrorKind {
             Self::StartupTimeout => 3005,
             Self::Backup => 4001,
             Self::GitHub => 4002,
+            Self::RateLimited => 4003,
             Self::Other => 9999,
         }
     }
@@ -121,6 +133,11 @@ impl AppError {
         )
     }
 
+    /// A version operation on a dev-mode instance, which runs from its checkout.
+    pub fn dev_instance_unversioned() -> Self {
+        Self::config("Dev-mode instances run from their source directory and have no version")
+    }
+
     pub fn config(message: impl Into<String>) -> Self {
         Self::with_detail(ErrorKind::Config, message)
     }
@@ -143,6 +160,36 @@ impl AppError {
         )
     }
 
+    pub fn checksum_mismatch(file: &str, expected: &str, actual: &str) -> Self {
+        Self::new(
+            ErrorKind::ChecksumMismatch,
+            HashMap::from([
+                ("file".to_string(), file.to_string()),
+                ("expected".to_string(), expected.to_string()),
+                ("actual".to_string(), actual.to_string()),
+            ]),
+        )
+    }
+
+    pub fn download_cancelled(url: &str) -> Self {
+        Self::new(
+            ErrorKind::DownloadCancelled,
+            HashMap::from([("url".to_string(), url.to_string())]),
+        )
+    }
+
+    /// `required` and `available` are in bytes.
+    pub fn insufficient_disk_space(path: &str, required: u64, available: u64) -> Self {
+        Self::new(
+            ErrorKind::InsufficientDiskSpace,
+            HashMap::from([
+                ("path".to_string(), path.to_string()),
+                ("required".to_string(), required.to_string()),
+                ("available".to_string(), available.to_string()),
+            ]),
+        )
+    }
+
     pub fn python(message: impl Into<String>) -> Self {
         Self::with_detail(ErrorKind::Python, message)
     }
@@ -184,6 +231,16 @@ impl AppError {
         Self::with_detail(ErrorKind::GitHub, message)
     }
 
+    /// `reset_at` is the RFC 3339 time the limit resets, when known.
+    pub fn rate_limited(reset_at: Option<String>) -> Self {
+        Self::new(
+            ErrorKind::RateLimited,
+            reset_at
+                .map(|reset_at| HashMap::from([("reset_at".to_string(), reset_at)]))
+                .unwrap_or_default(),
+        )
+    }
+
     pub fn other(message: impl Into<String>) -> Self {
         Self::with_detail(ErrorKind::Other, message)
     }
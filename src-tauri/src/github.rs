@@ -1,11 +1,25 @@
-use reqwest::Client;
+//! GitHub releases API access.
+//!
+//! Responses are cached on disk with their ETag, so repeated requests are
+//! answered with `304 Not Modified` without counting against the rate limit,
+//! and the cached copy is served when GitHub cannot be reached.
+
+use std::fs;
+use std::path::PathBuf;
+
+use reqwest::header::{ACCEPT, ETAG, IF_NONE_MATCH, RETRY_AFTER, USER_AGENT};
+use reqwest::{Client, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use crate::config::load_config;
-use crate::download::fetch_json;
-use crate::error::Result;
+use crate::download::USER_AGENT as LAUNCHER_USER_AGENT;
+use crate::error::{AppError, ErrorKind, Result};
+use crate::mirror::{with_mirror_fallback, MirrorKind};
+use crate::paths::get_github_cache_dir;
 
-const ASTRBOT_REPO: &str = "AstrBotDevs/AstrBot";
+/// Official AstrBot repository, the source of release versions.
+pub const ASTRBOT_REPO: &str = "AstrBotDevs/AstrBot";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRelease {
@@ -25,15 +39,24 @@ pub struct GitHubAsset {
     pub size: u64,
 }
 
+/// Semantic version of a release tag such as `v4.14.8`.
+pub fn parse_release_version(tag: &str) -> Option<semver::Version> {
+    semver::Version::parse(tag.trim_start_matches('v')).ok()
+}
+
+/// Releases API URL of `owner/repo`.
+fn releases_api_url(repo: &str, per_page: u32) -> String {
+    format!(
+        "https://api.github.com/repos/{}/releases?per_page={}",
+        repo, per_page
+    )
+}
+
 /// Build the API URL, optionally using a GitHub proxy.
 /// If proxy is empty, uses the official GitHub API.
 /// Proxy wraps the full original URL, e.g. `https://cdn.gh-proxy.org/https://api.github.com/...`.
 pub fn build_api_url(proxy: &str) -> String {
-    let raw = format!(
-        "https://api.github.com/repos/{}/releases?per_page=30",
-        ASTRBOT_REPO
-    );
-    wrap_with_proxy(proxy, &raw)
+    wrap_with_proxy(proxy, &releases_api_url(ASTRBOT_REPO, 30))
 }
 
 /// Wrap a URL with the GitHub proxy prefix.
@@ -49,30 +72,276 @@ pub fn wrap_with_proxy(proxy: &str, url: &str) -> String {
 
 /// Build a raw download URL, optionally using a GitHub proxy.
 pub fn build_download_url(proxy: &str, tag: &str) -> String {
-    let raw = format!("https://github.com/{}/archive/{}.zip", ASTRBOT_REPO, tag);
+    build_archive_url(proxy, ASTRBOT_REPO, tag)
+}
+
+/// Source archive URL of a ref in `owner/repo`, optionally using a GitHub proxy.
+pub fn build_archive_url(proxy: &str, repo: &str, git_ref: &str) -> String {
+    let raw = format!("https://github.com/{}/archive/{}.zip", repo, git_ref);
     wrap_with_proxy(proxy, &raw)
 }
 
+/// Check that `repo` has the `owner/repo` form GitHub accepts.
+pub fn validate_repo(repo: &str) -> Result<()> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repo.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(()),
+        _ => Err(AppError::config(format!(
+            "Repository must be in owner/repo form: {}",
+            repo
+        ))),
+    }
+}
+
 pub async fn fetch_releases(client: &Client) -> Result<Vec<GitHubRelease>> {
-    let config = load_config()?;
-    let url = build_api_url(&config.github_proxy);
-    fetch_json(client, &url).await
-}
-
-/// Fetch python-build-standalone releases with full asset information.
-pub async fn fetch_python_releases(client: &Client) -> Result<Vec<GitHubRelease>> {
-    let config = load_config()?;
-    let url = wrap_with_proxy(
-        &config.github_proxy,
-        "https://api.github.com/repos/astral-sh/python-build-standalone/releases?per_page=10",
-    );
-    fetch_json(client, &url).await
-}
-
-/// Get the source archive URL for a given tag, optionally using proxy.
-pub fn get_source_archive_url(tag: &str) -> String {
-    match load_config() {
-        Ok(config) => build_download_url(&config.github_proxy, tag),
-        Err(_) => format!("https://github.com/{}/archive/{}.zip", ASTRBOT_REPO, tag),
+    fetch_github_json(client, &releases_api_url(ASTRBOT_REPO, 30)).await
+}
+
+/// Fetch recent releases of `owner/repo` with full asset information.
+pub async fn fetch_repo_releases(client: &Client, repo: &str) -> Result<Vec<GitHubRelease>> {
+    fetch_github_json(client, &releases_api_url(repo, 10)).await
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubCommit {
+    sha: String,
+}
+
+/// Check that `git_ref` is a plain tag, branch or commit name that can be
+/// put into a URL path as-is.
+pub fn validate_git_ref(git_ref: &str) -> Result<()> {
+    let valid = !git_ref.is_empty()
+        && !git_ref.starts_with(['/', '-', '.'])
+        && !git_ref.ends_with(['/', '.'])
+        && !git_ref.contains("..")
+        && !git_ref.contains("//")
+        && git_ref
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | '+'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::config(format!("Invalid git ref: {}", git_ref)))
+    }
+}
+
+/// Resolve a tag, branch or commit SHA of `owner/repo` to its full commit SHA.
+///
+/// Unlike other API calls this never answers from the cache: a stale commit
+/// would make an outdated branch look up to date.
+pub async fn resolve_commit(client: &Client, repo: &str, git_ref: &str) -> Result<String> {
+    validate_repo(repo)?;
+    validate_git_ref(git_ref)?;
+    let url = format!("https://api.github.com/repos/{}/commits/{}", repo, git_ref);
+
+    let token = github_token();
+    let fetched = with_mirror_fallback(MirrorKind::GitHub, |proxy| {
+        let request_url = wrap_with_proxy(&proxy, &url);
+        let token = if proxy.is_empty() { token.as_str() } else { "" };
+        async move { request_github(client, &request_url, token, None).await }
+    })
+    .await?;
+    let GitHubResponse::Fresh { body, .. } = fetched else {
+        return Err(AppError::github(format!(
+            "{} answered Not Modified to an unconditional request",
+            url
+        )));
+    };
+
+    let commit: GitHubCommit = serde_json::from_value(body)
+        .map_err(|e| AppError::github(format!("Failed to parse response: {}", e)))?;
+    if commit.sha.len() != 40 || !commit.sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::github(format!(
+            "Unexpected commit SHA for {}: {}",
+            git_ref, commit.sha
+        )));
+    }
+    Ok(commit.sha)
+}
+
+/// A GitHub API response cached on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    etag: Option<String>,
+    fetched_at: String,
+    body: serde_json::Value,
+}
+
+enum GitHubResponse {
+    Fresh {
+        etag: Option<String>,
+        body: serde_json::Value,
+    },
+    NotModified,
+}
+
+fn cache_path(url: &str) -> PathBuf {
+    let name: String = url
+        .trim_start_matches("https://")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    get_github_cache_dir().join(format!("{}.json", name))
+}
+
+fn read_cache(url: &str) -> Option<CachedResponse> {
+    let content = fs::read_to_string(cache_path(url)).ok()?;
+    serde_json::from_str(&content)
+        .inspect_err(|e| log::warn!("Ignoring unreadable GitHub cache for {}: {}", url, e))
+        .ok()
+}
+
+fn write_cache(url: &str, cached: &CachedResponse) {
+    let path = cache_path(url);
+    let written = fs::create_dir_all(get_github_cache_dir())
+        .map_err(AppError::from)
+        .and_then(|()| Ok(serde_json::to_string(cached)?))
+        .and_then(|content| Ok(fs::write(&path, content)?));
+    if let Err(e) = written {
+        log::warn!("Failed to cache GitHub response in {:?}: {}", path, e);
     }
 }
+
+fn github_token() -> String {
+    load_config()
+        .map(|config| config.github_token.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// GET a GitHub API URL, trying each GitHub proxy in turn.
+///
+/// Sends the configured token, but only on direct requests so it never
+/// reaches a third-party proxy. Falls back to the cached response when
+/// GitHub cannot be reached.
+async fn fetch_github_json<T: DeserializeOwned>(client: &Client, url: &str) -> Result<T> {
+    let cached = read_cache(url);
+    let token = github_token();
+
+    let fetched = with_mirror_fallback(MirrorKind::GitHub, |proxy| {
+        let request_url = wrap_with_proxy(&proxy, url);
+        let token = if proxy.is_empty() { token.as_str() } else { "" };
+        let etag = cached.as_ref().and_then(|cached| cached.etag.as_deref());
+        async move { request_github(client, &request_url, token, etag).await }
+    })
+    .await;
+
+    let body = match (fetched, cached) {
+        (Ok(GitHubResponse::Fresh { etag, body }), _) => {
+            let fresh = CachedResponse {
+                etag,
+                fetched_at: chrono::Utc::now().to_rfc3339(),
+                body,
+            };
+            write_cache(url, &fresh);
+            fresh.body
+        }
+        (Ok(GitHubResponse::NotModified), Some(cached)) => cached.body,
+        (Ok(GitHubResponse::NotModified), None) => {
+            return Err(AppError::github(format!(
+                "{} answered Not Modified without a cached copy",
+                url
+            )));
+        }
+        (Err(e), Some(cached)) if e.kind() == ErrorKind::Network => {
+            log::warn!(
+                "GitHub unreachable, using response for {} cached at {}: {}",
+                url,
+                cached.fetched_at,
+                e
+            );
+            cached.body
+        }
+        (Err(e), _) => return Err(e),
+    };
+
+    serde_json::from_value(body)
+        .map_err(|e| AppError::github(format!("Failed to parse response: {}", e)))
+}
+
+async fn request_github(
+    client: &Client,
+    url: &str,
+    token: &str,
+    etag: Option<&str>,
+) -> Result<GitHubResponse> {
+    let mut request = client
+        .get(url)
+        .header(USER_AGENT, LAUNCHER_USER_AGENT)
+        .header(ACCEPT, "application/vnd.github+json");
+    if !token.is_empty() {
+        request = request.bearer_auth(token);
+    }
+    if let Some(etag) = etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| AppError::network_with_url(url, e.to_string()))?;
+
+    let status = resp.status();
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(GitHubResponse::NotModified);
+    }
+    if let Some(reset_at) = rate_limit_reset(&resp) {
+        return Err(AppError::rate_limited(reset_at));
+    }
+    if !status.is_success() {
+        return Err(AppError::network_with_url(url, status.to_string()));
+    }
+
+    let etag = resp
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = resp
+        .json()
+        .await
+        .map_err(|e| AppError::network_with_url(url, format!("Failed to read response: {}", e)))?;
+    Ok(GitHubResponse::Fresh { etag, body })
+}
+
+/// For rate-limited responses, the RFC 3339 time the limit resets, if known.
+///
+/// GitHub answers 403 or 429 and either exhausts `x-ratelimit-remaining` or,
+/// for secondary limits, sends `retry-after`.
+fn rate_limit_reset(resp: &Response) -> Option<Option<String>> {
+    let status = resp.status();
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let header = |name: &str| {
+        resp.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok())
+    };
+    let retry_after = header(RETRY_AFTER.as_str());
+    let exhausted = header("x-ratelimit-remaining") == Some(0);
+    if status == StatusCode::FORBIDDEN && !exhausted && retry_after.is_none() {
+        return None;
+    }
+
+    let reset_at = match retry_after {
+        Some(secs) => Some(chrono::Utc::now() + chrono::TimeDelta::seconds(secs)),
+        None => header("x-ratelimit-reset")
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0)),
+    };
+    Some(reset_at.map(|reset_at| reset_at.to_rfc3339()))
+}
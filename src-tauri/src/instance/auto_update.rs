@@ -0,0 +1,281 @@
+//! Instance update checks and unattended updates with rollback.
+//!
+//! Each instance follows a release channel. Checks record the newest newer
+//! release per instance, which `list_instances` reports. Instances opted into
+//! auto-update are moved to it through the `update_instance` pipeline and
+//! started as a health check; if anything fails, the previous version and a
+//! pre-update data backup are put back.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use reqwest::Client;
+use tauri::AppHandle;
+
+use super::cleanup::clear_instance_data;
+use super::crud::update_instance;
+use super::layout::instance_data_dir;
+use super::lifecycle::{start_instance, stop_instance};
+use crate::backup::{create_backup, delete_backup, restore_data_to_instance};
+use crate::config::{load_config, AppConfig, InstanceConfig, UpdateChannel};
+use crate::download::download_version;
+use crate::error::{AppError, Result};
+use crate::github::{fetch_releases, parse_release_version, GitHubRelease};
+use crate::process::ProcessManager;
+
+#[derive(Default)]
+struct UpdateState {
+    /// Instance id to the newest release it can update to.
+    available: HashMap<String, GitHubRelease>,
+    /// Instance id to the release an unattended update rolled back from,
+    /// so it is not retried until the launcher restarts.
+    failed: HashMap<String, String>,
+}
+
+static UPDATE_STATE: OnceLock<RwLock<UpdateState>> = OnceLock::new();
+
+fn update_state() -> &'static RwLock<UpdateState> {
+    UPDATE_STATE.get_or_init(|| RwLock::new(UpdateState::default()))
+}
+
+/// Newest release an instance can update to, as of the last check.
+pub fn available_update(instance_id: &str) -> Option<String> {
+    update_state()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .available
+        .get(instance_id)
+        .map(|release| release.tag_name.clone())
+}
+
+/// Newest release on `channel` that is newer than `current`.
+fn newest_release<'a>(
+    releases: &'a [GitHubRelease],
+    channel: UpdateChannel,
+    current: &str,
+) -> Option<&'a GitHubRelease> {
+    let current = parse_release_version(current)?;
+    releases
+        .iter()
+        .filter(|release| channel == UpdateChannel::Prerelease || !release.prerelease)
+        .filter_map(|release| {
+            parse_release_version(&release.tag_name).map(|version| (version, release))
+        })
+        .filter(|(version, _)| *version > current)
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, release)| release)
+}
+
+/// Whether an instance runs an official release that can be updated.
+///
+/// Dev-mode instances run a checkout, and versions built from a git ref are
+/// updated from their branch instead.
+fn follows_releases(config: &AppConfig, instance: &InstanceConfig) -> bool {
+    instance.source_dir.is_none()
+        && !config
+            .installed_versions
+            .iter()
+            .any(|v| v.version == instance.version && v.source.is_some())
+}
+
+/// Check every instance for a newer release on its channel.
+///
+/// Returns the instance ids with an update available and the release tag.
+pub async fn refresh_instance_updates(client: &Client) -> Result<HashMap<String, String>> {
+    let config = load_config()?;
+    if !config.check_instance_update {
+        update_state()
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .available
+            .clear();
+        return Ok(HashMap::new());
+    }
+
+    let releases = fetch_releases(client).await?;
+    let available: HashMap<String, GitHubRelease> = config
+        .instances
+        .iter()
+        .filter(|(_, instance)| follows_releases(&config, instance))
+        .filter_map(|(id, instance)| {
+            newest_release(&releases, instance.update_channel, &instance.version)
+                .map(|release| (id.clone(), release.clone()))
+        })
+        .collect();
+
+    let summary = available
+        .iter()
+        .map(|(id, release)| (id.clone(), release.tag_name.clone()))
+        .collect();
+    update_state()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .available = available;
+    Ok(summary)
+}
+
+/// Update every auto-update instance with an available update, one at a time.
+///
+/// Failures are logged and rolled back; they never stop the other updates.
+pub async fn run_auto_updates(
+    client: &Client,
+    app_handle: &AppHandle,
+    process_manager: &Arc<ProcessManager>,
+) -> Result<()> {
+    let config = load_config()?;
+    let pending: Vec<(String, GitHubRelease)> = {
+        let state = update_state().read().unwrap_or_else(|e| e.into_inner());
+        state
+            .available
+            .iter()
+            .filter(|(id, _)| config.instances.get(*id).is_some_and(|i| i.auto_update))
+            .filter(|(id, release)| state.failed.get(*id) != Some(&release.tag_name))
+            .map(|(id, release)| (id.clone(), release.clone()))
+            .collect()
+    };
+
+    for (instance_id, release) in pending {
+        match auto_update_instance(client, &instance_id, &release, app_handle, process_manager)
+            .await
+        {
+            Ok(()) => {
+                update_state()
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .available
+                    .remove(&instance_id);
+            }
+            Err(e) => {
+                log::error!(
+                    "Auto-update of instance {} to {} failed: {}",
+                    instance_id,
+                    release.tag_name,
+                    e
+                );
+                update_state()
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .failed
+                    .insert(instance_id, release.tag_name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Update one instance to `release`, rolling back if it does not come up.
+///
+/// The instance is started to verify the update and left in the running state
+/// it had before.
+async fn auto_update_instance(
+    client: &Client,
+    instance_id: &str,
+    release: &GitHubRelease,
+    app_handle: &AppHandle,
+    process_manager: &Arc<ProcessManager>,
+) -> Result<()> {
+    let config = load_config()?;
+    let previous = config
+        .instances
+        .get(instance_id)
+        .ok_or_else(|| AppError::instance_not_found(instance_id))?
+        .version
+        .clone();
+    if !config
+        .installed_versions
+        .iter()
+        .any(|v| v.version == release.tag_name)
+    {
+        download_version(client, release).await?;
+    }
+
+    let was_running = process_manager.is_running(instance_id).await;
+    if was_running {
+        stop_instance(instance_id, Arc::clone(process_manager)).await?;
+    }
+
+    let backup = if instance_data_dir(instance_id).exists() {
+        Some(create_backup(instance_id, true)?)
+    } else {
+        None
+    };
+
+    log::info!(
+        "Auto-updating instance {} from {} to {}",
+        instance_id,
+        previous,
+        release.tag_name
+    );
+    let updated = async {
+        update_instance(instance_id, None, Some(&release.tag_name), None, app_handle).await?;
+        start_instance(instance_id, app_handle, Arc::clone(process_manager)).await
+    }
+    .await;
+
+    let result = match updated {
+        Ok(_) => {
+            if !was_running {
+                stop_instance(instance_id, Arc::clone(process_manager)).await?;
+            }
+            log::info!("Instance {} updated to {}", instance_id, release.tag_name);
+            Ok(())
+        }
+        Err(e) => {
+            log::warn!(
+                "Instance {} failed after updating to {}, rolling back to {}: {}",
+                instance_id,
+                release.tag_name,
+                previous,
+                e
+            );
+            rollback(
+                instance_id,
+                &previous,
+                backup.as_deref(),
+                app_handle,
+                process_manager,
+            )
+            .await?;
+            if was_running {
+                start_instance(instance_id, app_handle, Arc::clone(process_manager)).await?;
+            }
+            Err(AppError::other(format!(
+                "Rolled back to {} after update failed: {}",
+                previous, e
+            )))
+        }
+    };
+
+    if let Some(backup) = &backup {
+        if let Err(e) = delete_backup(backup) {
+            log::warn!("Failed to delete pre-update backup {}: {}", backup, e);
+        }
+    }
+    result
+}
+
+/// Put an instance back on `previous` with the data it had before the update.
+async fn rollback(
+    instance_id: &str,
+    previous: &str,
+    backup: Option<&str>,
+    app_handle: &AppHandle,
+    process_manager: &Arc<ProcessManager>,
+) -> Result<()> {
+    if process_manager.is_running(instance_id).await {
+        stop_instance(instance_id, Arc::clone(process_manager)).await?;
+    }
+
+    // A failed deployment leaves the config on the previous version, and the
+    // next start redeploys it.
+    update_instance(instance_id, None, Some(previous), None, app_handle).await?;
+
+    // The new version may have migrated the data, so restore the copy taken
+    // before the update.
+    if let Some(backup) = backup {
+        clear_instance_data(instance_id)?;
+        restore_data_to_instance(backup, instance_id)?;
+    }
+    Ok(())
+}
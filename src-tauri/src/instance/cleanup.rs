@@ -5,6 +5,7 @@ use std::path::Path;
 use walkdir::WalkDir;
 
 use super::deploy::remove_deploy_marker;
+use super::layout::instance_data_dir;
 use crate::error::{AppError, Result};
 use crate::paths::{get_instance_core_dir, get_instance_venv_dir};
 use crate::validation::validate_instance_id;
@@ -13,8 +14,7 @@ use crate::validation::validate_instance_id;
 pub fn clear_instance_data(instance_id: &str) -> Result<()> {
     validate_instance_id(instance_id)?;
 
-    let core_dir = get_instance_core_dir(instance_id);
-    let data_dir = core_dir.join("data");
+    let data_dir = instance_data_dir(instance_id);
 
     if data_dir.exists() {
         std::fs::remove_dir_all(&data_dir)
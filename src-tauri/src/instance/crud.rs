@@ -1,15 +1,30 @@
 //! Instance CRUD operations.
 
+use std::path::Path;
 use std::sync::Arc;
 
 use tauri::AppHandle;
 
-use super::deploy::{deploy_instance_with_version, emit_progress, remove_deploy_marker};
+use super::auto_update::available_update;
+use super::cleanup::clear_instance_venv;
+use super::deploy::{
+    deploy_instance_with_version, emit_progress, remove_deploy_marker, sync_plugin_requirements,
+};
+use super::layout::instance_data_dir;
 use super::types::{CmdConfig, InstanceStatus};
 use crate::backup::{create_backup, delete_backup, restore_data_to_instance};
-use crate::config::{load_config, with_config_mut, AppConfig, InstanceConfig};
+use crate::component::{
+    component_for_instance, parse_tool_ids, python_compatibility_warning, ComponentId,
+    ComponentKind,
+};
+use crate::config::{load_config, with_config_mut, AppConfig, InstanceConfig, UpdateChannel};
+use crate::disk_usage::disk_size;
 use crate::error::{AppError, Result};
-use crate::paths::{get_instance_core_dir, get_instance_dir, get_instance_venv_dir};
+use crate::paths::{
+    get_data_dir, get_instance_core_dir, get_instance_dir, get_instance_venv_dir,
+    get_version_zip_path,
+};
+use crate::preflight::{ensure_free_space, extracted_size};
 use crate::process::{InstanceRuntimeSnapshot, ProcessManager};
 use crate::validation::validate_instance_id;
 
@@ -25,13 +40,26 @@ fn ensure_version_installed(config: &AppConfig, version: &str) -> Result<()> {
     }
 }
 
+/// Extra space a version change needs at its peak.
+///
+/// The data backup is written first, then the old code and venv are replaced
+/// by the new version's code and a venv assumed to be as large as the current.
+fn version_change_space(instance_id: &str, new_version: &str) -> u64 {
+    let core = disk_size(&get_instance_core_dir(instance_id));
+    let data = disk_size(&instance_data_dir(instance_id));
+    let venv = disk_size(&get_instance_venv_dir(instance_id));
+    let new_core = extracted_size(&get_version_zip_path(new_version));
+
+    let freed = core.saturating_sub(data) + venv;
+    data.max((data + new_core + venv).saturating_sub(freed))
+}
+
 pub(super) fn is_dashboard_enabled(instance_id: &str) -> bool {
     if validate_instance_id(instance_id).is_err() {
         return false;
     }
 
-    let core_dir = get_instance_core_dir(instance_id);
-    let config_path = core_dir.join("data").join("cmd_config.json");
+    let config_path = instance_data_dir(instance_id).join("cmd_config.json");
 
     if !config_path.exists() {
         return true;
@@ -86,6 +114,12 @@ pub fn create_instance(name: &str, version: &str, port: u16) -> Result<()> {
             version,
             port,
             created_at: chrono::Utc::now().to_rfc3339(),
+            python_component: None,
+            tools: Vec::new(),
+            source_dir: None,
+            isolate_data: false,
+            update_channel: UpdateChannel::default(),
+            auto_update: false,
         };
 
         config.instances.insert(key, instance);
@@ -93,6 +127,66 @@ pub fn create_instance(name: &str, version: &str, port: u16) -> Result<()> {
     })
 }
 
+/// Create a dev-mode instance running from a local source checkout.
+///
+/// The checkout is never modified by the launcher; with `isolate_data` the
+/// instance keeps its `data/` in the instance directory instead.
+pub fn create_dev_instance(
+    name: &str,
+    source_dir: &Path,
+    port: u16,
+    isolate_data: bool,
+) -> Result<()> {
+    if !source_dir.is_absolute() || !source_dir.join("main.py").is_file() {
+        return Err(AppError::other(format!(
+            "{:?} is not an AstrBot source checkout: main.py not found",
+            source_dir
+        )));
+    }
+    // Instance directories are removed with their instance, so a checkout
+    // inside the data dir could be deleted.
+    let canonical = source_dir
+        .canonicalize()
+        .map_err(|e| AppError::io(format!("Failed to resolve {:?}: {}", source_dir, e)))?;
+    if get_data_dir()
+        .canonicalize()
+        .is_ok_and(|data_dir| canonical.starts_with(data_dir))
+    {
+        return Err(AppError::config(
+            "Source directory must be outside the launcher data directory",
+        ));
+    }
+    let source_dir = source_dir
+        .to_str()
+        .ok_or_else(|| AppError::io("source directory path is not valid UTF-8"))?
+        .to_string();
+
+    let id = uuid::Uuid::new_v4().to_string();
+
+    let instance_dir = get_instance_dir(&id);
+    std::fs::create_dir_all(&instance_dir)
+        .map_err(|e| AppError::io(format!("Failed to create instance dir: {}", e)))?;
+
+    let name = name.to_string();
+    with_config_mut(move |config| {
+        let instance = InstanceConfig {
+            name,
+            version: String::new(),
+            port,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            python_component: None,
+            tools: Vec::new(),
+            source_dir: Some(source_dir),
+            isolate_data,
+            update_channel: UpdateChannel::default(),
+            auto_update: false,
+        };
+
+        config.instances.insert(id, instance);
+        Ok(())
+    })
+}
+
 /// Delete an instance.
 pub async fn delete_instance(
     instance_id: &str,
@@ -148,6 +242,10 @@ pub async fn update_instance(
             .ok_or_else(|| AppError::instance_not_found(instance_id))?;
         if let Some(v) = version {
             if instance.version != v {
+                // Replacing the code would delete the developer's checkout.
+                if instance.source_dir.is_some() {
+                    return Err(AppError::dev_instance_unversioned());
+                }
                 ensure_version_installed(&config, v)?;
                 Some(v.to_string())
             } else {
@@ -160,6 +258,10 @@ pub async fn update_instance(
 
     if let Some(ref new_version) = new_version {
         // Version change
+        ensure_free_space(
+            &get_instance_dir(instance_id),
+            version_change_space(instance_id, new_version),
+        )?;
 
         // Backup
         emit_progress(app_handle, instance_id, "backup", "正在备份数据...", 5);
@@ -194,6 +296,9 @@ pub async fn update_instance(
             emit_progress(app_handle, instance_id, "restore", "正在还原数据...", 92);
             restore_data_to_instance(bp, instance_id)?;
             emit_progress(app_handle, instance_id, "restore", "数据还原完成", 95);
+
+            // Restored plugins need their requirements in the fresh venv
+            sync_plugin_requirements(instance_id, app_handle, (96, 98)).await?;
         }
 
         // Update config(version + optional name/port) after the operation completes successfully.
@@ -251,6 +356,90 @@ pub async fn update_instance(
     }
 }
 
+/// Set or clear an instance's Python component override.
+///
+/// Returns a warning when the component does not match the version's Python
+/// requirement. If the effective interpreter changes, the venv is cleared so
+/// the next start rebuilds it.
+pub fn set_instance_python(
+    instance_id: &str,
+    python_component: Option<&str>,
+) -> Result<Option<String>> {
+    validate_instance_id(instance_id)?;
+
+    let component = python_component
+        .map(|id| {
+            ComponentId::from_str_id(id)
+                .filter(|c| c.kind() == ComponentKind::Python)
+                .ok_or_else(|| AppError::config(format!("Unknown Python component: {}", id)))
+        })
+        .transpose()?;
+
+    let version = {
+        let config = load_config()?;
+        config
+            .instances
+            .get(instance_id)
+            .ok_or_else(|| AppError::instance_not_found(instance_id))?
+            .version
+            .clone()
+    };
+    let previous = component_for_instance(instance_id, &version).ok();
+
+    let id = instance_id.to_string();
+    let override_id = component.map(|c| c.dir_name().to_string());
+    with_config_mut(move |config| {
+        let instance = config
+            .instances
+            .get_mut(&id)
+            .ok_or_else(|| AppError::instance_not_found(&id))?;
+        instance.python_component = override_id;
+        Ok(())
+    })?;
+
+    let current = component_for_instance(instance_id, &version).ok();
+    if current != previous {
+        log::info!(
+            "Instance {} switched Python from {:?} to {:?}, clearing venv",
+            instance_id,
+            previous.map(ComponentId::dir_name),
+            current.map(ComponentId::dir_name)
+        );
+        clear_instance_venv(instance_id)?;
+    }
+
+    // Dev-mode instances have no version to check the component against.
+    if version.is_empty() {
+        return Ok(None);
+    }
+    Ok(component.and_then(|c| python_compatibility_warning(c, &version)))
+}
+
+/// Set the tool components put on an instance's PATH.
+///
+/// Takes effect on the next start; the venv is unaffected.
+pub fn set_instance_tools(instance_id: &str, tool_ids: &[String]) -> Result<()> {
+    validate_instance_id(instance_id)?;
+
+    let mut tools: Vec<String> = Vec::new();
+    for tool in parse_tool_ids(tool_ids)? {
+        let tool = tool.dir_name().to_string();
+        if !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+
+    let id = instance_id.to_string();
+    with_config_mut(move |config| {
+        let instance = config
+            .instances
+            .get_mut(&id)
+            .ok_or_else(|| AppError::instance_not_found(&id))?;
+        instance.tools = tools;
+        Ok(())
+    })
+}
+
 /// List all instances with their running status.
 pub async fn list_instances(process_manager: &ProcessManager) -> Result<Vec<InstanceStatus>> {
     let config = load_config()?;
@@ -271,6 +460,11 @@ pub async fn list_instances(process_manager: &ProcessManager) -> Result<Vec<Inst
                         dashboard_enabled: is_dashboard_enabled(&id),
                     });
 
+            let python_component = component_for_instance(&id, &inst.version)
+                .map(|c| c.dir_name().to_string())
+                .unwrap_or_default();
+            let update_available = available_update(&id);
+
             InstanceStatus {
                 id,
                 name: inst.name,
@@ -279,6 +473,14 @@ pub async fn list_instances(process_manager: &ProcessManager) -> Result<Vec<Inst
                 version: inst.version,
                 dashboard_enabled: snapshot.dashboard_enabled,
                 configured_port: inst.port,
+                python_component,
+                python_override: inst.python_component,
+                tools: inst.tools,
+                source_dir: inst.source_dir,
+                isolate_data: inst.isolate_data,
+                update_channel: inst.update_channel,
+                auto_update: inst.auto_update,
+                update_available,
             }
         })
         .collect())
@@ -1,20 +1,31 @@
 //! Instance deployment functionality.
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use tauri::{AppHandle, Emitter as _};
 use tokio::process::Command;
 
-use super::types::DeployProgress;
+use super::layout::{instance_data_dir, is_instance_deployed};
+use super::manifest::{
+    find_dependency_manifest, read_lock_constraints, read_pyproject_dependencies,
+    DependencyManifest,
+};
+use super::marker::{current_fingerprint, read_deploy_marker, write_deploy_marker};
+use super::types::{DeployProgress, PluginDepsResult};
 use crate::archive::extract_zip_flat;
-use crate::component::get_python_for_version;
+use crate::checksum::verify_sha256;
+use crate::component::get_python_for_instance;
 use crate::config::load_config;
 use crate::error::{AppError, Result};
+use crate::mirror::{mark_mirror_failed, mark_mirror_healthy, mirror_candidates, MirrorKind};
 use crate::paths::{
-    get_instance_core_dir, get_instance_deploy_marker, get_instance_venv_dir, get_venv_python,
-    is_instance_deployed,
+    get_instance_core_dir, get_instance_deploy_marker, get_instance_venv_dir, get_pip_cache_dir,
+    get_venv_python, get_wheelhouse_dir,
 };
+use crate::preflight::{ensure_free_space, extracted_size};
+use crate::proxy::pip_proxy_env;
 use crate::validation::validate_instance_id;
 
 /// Emit deployment progress event.
@@ -24,6 +35,17 @@ pub fn emit_progress(
     step: &str,
     message: &str,
     progress: u8,
+) {
+    emit_progress_with_plugins(app_handle, instance_id, step, message, progress, Vec::new());
+}
+
+fn emit_progress_with_plugins(
+    app_handle: &AppHandle,
+    instance_id: &str,
+    step: &str,
+    message: &str,
+    progress: u8,
+    plugin_results: Vec<PluginDepsResult>,
 ) {
     let _ = app_handle.emit(
         "deploy-progress",
@@ -32,6 +54,7 @@ pub fn emit_progress(
             step: step.to_string(),
             message: message.to_string(),
             progress,
+            plugin_results,
         },
     );
 }
@@ -56,7 +79,18 @@ pub async fn deploy_instance_with_version(
 ) -> Result<()> {
     validate_instance_id(instance_id)?;
 
-    let was_deployed = is_instance_deployed(instance_id);
+    let source_dir = load_config()?
+        .instances
+        .get(instance_id)
+        .and_then(|instance| instance.source_dir.clone());
+    if let Some(source_dir) = source_dir {
+        return deploy_source_instance(instance_id, version, Path::new(&source_dir), app_handle)
+            .await;
+    }
+
+    // Extracted code can only be reused when it belongs to the requested version.
+    let was_deployed = is_instance_deployed(instance_id)
+        && read_deploy_marker(instance_id).is_some_and(|marker| marker.version == version);
 
     // Any new deployment attempt starts from "not deployed" state.
     remove_deploy_marker(instance_id)?;
@@ -77,7 +111,6 @@ pub async fn deploy_instance_with_version(
     }
 
     let core_dir = get_instance_core_dir(instance_id);
-    let venv_dir = get_instance_venv_dir(instance_id);
 
     // Extract zip.
     // We only skip extraction when the previous deployment was fully valid.
@@ -102,22 +135,77 @@ pub async fn deploy_instance_with_version(
             .map_err(|e| AppError::io(format!("Failed to create core dir: {}", e)))?;
         clear_core_except_data(&core_dir)?;
 
+        if let Some(expected) = installed.sha256.as_deref() {
+            verify_sha256(&zip_path, expected)?;
+        }
+        ensure_free_space(&core_dir, extracted_size(&zip_path))?;
         extract_zip_flat(&zip_path, &core_dir)?;
         emit_progress(app_handle, instance_id, "extract", "代码解压完成", 30);
     }
 
+    // Versions imported from an offline kit install from their bundled wheels.
+    let wheelhouse_dir = get_wheelhouse_dir(version);
+    let wheelhouse = wheelhouse_dir.is_dir().then_some(wheelhouse_dir.as_path());
+    build_environment(instance_id, version, &core_dir, wheelhouse, app_handle).await
+}
+
+/// Deploy a dev-mode instance running from a local checkout.
+///
+/// Only the venv is built; the checkout is used as-is and never modified.
+/// The deployment goes stale when its dependency manifest changes, so the
+/// next start re-syncs the requirements.
+async fn deploy_source_instance(
+    instance_id: &str,
+    version: &str,
+    source_dir: &Path,
+    app_handle: &AppHandle,
+) -> Result<()> {
+    remove_deploy_marker(instance_id)?;
+
+    if !source_dir.join("main.py").is_file() {
+        return Err(AppError::io(format!(
+            "main.py not found in source directory {:?}",
+            source_dir
+        )));
+    }
+    emit_progress(
+        app_handle,
+        instance_id,
+        "extract",
+        "使用本地源码目录，跳过解压",
+        30,
+    );
+
+    build_environment(instance_id, version, source_dir, None, app_handle).await
+}
+
+/// Create the venv, install core and plugin requirements and write the marker.
+async fn build_environment(
+    instance_id: &str,
+    version: &str,
+    core_dir: &Path,
+    wheelhouse: Option<&Path>,
+    app_handle: &AppHandle,
+) -> Result<()> {
+    let venv_dir = get_instance_venv_dir(instance_id);
+
     // Create venv
     emit_progress(app_handle, instance_id, "venv", "正在创建虚拟环境...", 40);
-    create_venv(&venv_dir, version).await?;
+    create_venv(&venv_dir, instance_id, version).await?;
     emit_progress(app_handle, instance_id, "venv", "虚拟环境创建完成", 50);
 
     // Install requirements
     emit_progress(app_handle, instance_id, "deps", "正在安装依赖...", 60);
     let venv_python = get_venv_python(&venv_dir);
-    install_requirements(&venv_python, &core_dir).await?;
+    install_requirements(&venv_python, core_dir, wheelhouse).await?;
     emit_progress(app_handle, instance_id, "deps", "依赖安装完成", 90);
 
-    write_deploy_marker(instance_id, version)?;
+    // Plugin requirements are best-effort and never fail the deployment
+    let data_dir = instance_data_dir(instance_id);
+    install_plugin_requirements(instance_id, &venv_python, &data_dir, app_handle, (90, 92)).await;
+
+    let fingerprint = current_fingerprint(instance_id, version).await?;
+    write_deploy_marker(instance_id, version, fingerprint)?;
 
     // Note: "done" is emitted by start_instance after the instance is truly running
 
@@ -168,19 +256,23 @@ pub fn remove_deploy_marker(instance_id: &str) -> Result<()> {
     Ok(())
 }
 
-fn write_deploy_marker(instance_id: &str, version: &str) -> Result<()> {
-    let marker = get_instance_deploy_marker(instance_id);
-    if let Some(parent) = marker.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| AppError::io(format!("Failed to create marker directory: {}", e)))?;
+/// Remove the deployment markers of all instances on `version`, so their next
+/// start extracts the version's current archive. Returns how many were reset.
+pub fn invalidate_version_deployments(version: &str) -> Result<usize> {
+    let config = load_config()?;
+    let mut count = 0;
+    for (instance_id, instance) in &config.instances {
+        if instance.version == version {
+            remove_deploy_marker(instance_id)?;
+            count += 1;
+        }
     }
-    fs::write(&marker, format!("version={}\n", version))
-        .map_err(|e| AppError::io(format!("Failed to write deployment marker: {}", e)))
+    Ok(count)
 }
 
-/// Create a virtual environment using the appropriate Python for the version.
-async fn create_venv(venv_dir: &Path, version: &str) -> Result<()> {
-    let python_exe = get_python_for_version(version)?;
+/// Create a virtual environment using the Python selected for the instance.
+async fn create_venv(venv_dir: &Path, instance_id: &str, version: &str) -> Result<()> {
+    let python_exe = get_python_for_instance(instance_id, version)?;
 
     if venv_dir.exists() {
         let venv_python = get_venv_python(venv_dir);
@@ -213,18 +305,242 @@ async fn create_venv(venv_dir: &Path, version: &str) -> Result<()> {
     Ok(())
 }
 
-/// Install requirements into an instance's venv.
-async fn install_requirements(venv_python: &Path, core_path: &Path) -> Result<()> {
-    let requirements_path = core_path.join("requirements.txt");
+/// Install the core dependencies into an instance's venv.
+///
+/// Installs from `wheelhouse` alone when given, otherwise from the index.
+async fn install_requirements(
+    venv_python: &Path,
+    core_path: &Path,
+    wheelhouse: Option<&Path>,
+) -> Result<()> {
+    let venv_dir = venv_python
+        .parent()
+        .and_then(Path::parent)
+        .ok_or_else(|| AppError::io("Invalid venv python path"))?;
+
+    match core_requirement_files(core_path, venv_dir)? {
+        Some((requirements_path, constraints_path)) => {
+            pip_install_requirements(
+                venv_python,
+                &requirements_path,
+                constraints_path.as_deref(),
+                wheelhouse,
+            )
+            .await
+        }
+        None => Ok(()),
+    }
+}
+
+/// Download the core dependencies of a version archive as wheels into `dest`.
+///
+/// The archive is extracted into `work_dir`, which the caller cleans up.
+pub async fn download_core_wheels(
+    python_exe: &Path,
+    version_zip: &Path,
+    work_dir: &Path,
+    dest: &Path,
+) -> Result<()> {
+    let core_path = work_dir.join("core");
+    extract_zip_flat(version_zip, &core_path)?;
 
-    if !requirements_path.exists() {
+    let Some((requirements_path, constraints_path)) = core_requirement_files(&core_path, work_dir)?
+    else {
         return Ok(());
+    };
+
+    let mut args = vec!["-m".to_string(), "pip".to_string(), "download".to_string()];
+    args.extend(requirement_args(
+        &requirements_path,
+        constraints_path.as_deref(),
+    )?);
+    args.push("-d".to_string());
+    args.push(
+        dest.to_str()
+            .ok_or_else(|| AppError::io("wheelhouse path is not valid UTF-8"))?
+            .to_string(),
+    );
+
+    run_pip_with_index(python_exe, args, None, "download requirements").await
+}
+
+/// Resolve the requirement and constraint files for the core dependencies.
+///
+/// Uses requirements.txt when present, otherwise the `[project]` dependencies of
+/// pyproject.toml pinned by its lock file, written to generated files in
+/// `scratch_dir` so the source tree stays untouched. Returns `None` when there
+/// is nothing to install. A source without any dependency manifest is rejected
+/// rather than deployed with an empty venv.
+fn core_requirement_files(
+    core_path: &Path,
+    scratch_dir: &Path,
+) -> Result<Option<(PathBuf, Option<PathBuf>)>> {
+    let (pyproject, lock) = match find_dependency_manifest(core_path) {
+        Some(DependencyManifest::Requirements(requirements_path)) => {
+            return Ok(Some((requirements_path, None)));
+        }
+        Some(DependencyManifest::Pyproject { pyproject, lock }) => (pyproject, lock),
+        None => {
+            return Err(AppError::python(format!(
+                "No requirements.txt or pyproject.toml found in {:?}",
+                core_path
+            )));
+        }
+    };
+
+    let dependencies = read_pyproject_dependencies(&pyproject)?;
+    if dependencies.is_empty() {
+        return Ok(None);
+    }
+
+    let requirements_path = scratch_dir.join("launcher-requirements.txt");
+    fs::write(&requirements_path, dependencies.join("\n"))
+        .map_err(|e| AppError::io(format!("Failed to write {:?}: {}", requirements_path, e)))?;
+
+    let constraints_path = match lock {
+        Some(lock) => {
+            let constraints = read_lock_constraints(&lock)?;
+            let path = scratch_dir.join("launcher-constraints.txt");
+            fs::write(&path, constraints.join("\n"))
+                .map_err(|e| AppError::io(format!("Failed to write {:?}: {}", path, e)))?;
+            Some(path)
+        }
+        None => None,
+    };
+
+    Ok(Some((requirements_path, constraints_path)))
+}
+
+/// Install plugin requirements for an already deployed instance, e.g. after its
+/// data has been restored from a backup.
+///
+/// Does nothing if the instance has no venv yet; the next deployment covers it.
+pub async fn sync_plugin_requirements(
+    instance_id: &str,
+    app_handle: &AppHandle,
+    progress: (u8, u8),
+) -> Result<Vec<PluginDepsResult>> {
+    validate_instance_id(instance_id)?;
+
+    let venv_python = get_venv_python(&get_instance_venv_dir(instance_id));
+    if !venv_python.exists() {
+        return Ok(Vec::new());
+    }
+
+    let data_dir = instance_data_dir(instance_id);
+    let results =
+        install_plugin_requirements(instance_id, &venv_python, &data_dir, app_handle, progress)
+            .await;
+    Ok(results)
+}
+
+/// Install each plugin's requirements.txt separately, tolerating failures.
+async fn install_plugin_requirements(
+    instance_id: &str,
+    venv_python: &Path,
+    data_dir: &Path,
+    app_handle: &AppHandle,
+    (start, end): (u8, u8),
+) -> Vec<PluginDepsResult> {
+    let plugins = find_plugin_requirements(data_dir);
+    if plugins.is_empty() {
+        return Vec::new();
     }
 
+    emit_progress(
+        app_handle,
+        instance_id,
+        "plugin_deps",
+        "正在安装插件依赖...",
+        start,
+    );
+
+    let mut results = Vec::with_capacity(plugins.len());
+    for (plugin, requirements_path) in plugins {
+        let installed = pip_install_requirements(venv_python, &requirements_path, None, None).await;
+        let result = match installed {
+            Ok(()) => PluginDepsResult {
+                plugin,
+                success: true,
+                message: String::new(),
+            },
+            Err(e) => {
+                log::warn!(
+                    "Failed to install requirements for plugin {} of instance {}: {}",
+                    plugin,
+                    instance_id,
+                    e
+                );
+                PluginDepsResult {
+                    plugin,
+                    success: false,
+                    message: e.to_string(),
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    let message = if failed == 0 {
+        "插件依赖安装完成".to_string()
+    } else {
+        format!("插件依赖安装完成，{} 个插件失败", failed)
+    };
+    emit_progress_with_plugins(
+        app_handle,
+        instance_id,
+        "plugin_deps",
+        &message,
+        end,
+        results.clone(),
+    );
+
+    results
+}
+
+/// Find `data/plugins/*/requirements.txt`, sorted by plugin name.
+fn find_plugin_requirements(data_dir: &Path) -> Vec<(String, PathBuf)> {
+    let plugins_dir = data_dir.join("plugins");
+    let Ok(entries) = fs::read_dir(&plugins_dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|entry| {
+            let requirements = entry.path().join("requirements.txt");
+            if !requirements.is_file() {
+                return None;
+            }
+            let name = entry.file_name().to_str()?.to_string();
+            Some((name, requirements))
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.0.cmp(&b.0));
+    plugins
+}
+
+/// Run `pip install -r <requirements> [-c <constraints>]` in the given venv.
+async fn pip_install_requirements(
+    venv_python: &Path,
+    requirements_path: &Path,
+    constraints_path: Option<&Path>,
+    wheelhouse: Option<&Path>,
+) -> Result<()> {
+    let mut args = vec!["-m".to_string(), "pip".to_string(), "install".to_string()];
+    args.extend(requirement_args(requirements_path, constraints_path)?);
+
+    run_pip_with_index(venv_python, args, wheelhouse, "install requirements").await
+}
+
+/// `-r <requirements> [-c <constraints>]` arguments for pip.
+fn requirement_args(
+    requirements_path: &Path,
+    constraints_path: Option<&Path>,
+) -> Result<Vec<String>> {
     let mut args = vec![
-        "-m".to_string(),
-        "pip".to_string(),
-        "install".to_string(),
         "-r".to_string(),
         requirements_path
             .to_str()
@@ -232,26 +548,137 @@ async fn install_requirements(venv_python: &Path, core_path: &Path) -> Result<()
             .to_string(),
     ];
 
-    // Apply PyPI mirror if configured
-    if let Ok(config) = load_config() {
-        let mirror = config.pypi_mirror.as_str();
+    if let Some(constraints_path) = constraints_path {
+        args.push("-c".to_string());
+        args.push(
+            constraints_path
+                .to_str()
+                .ok_or_else(|| AppError::io("constraints path is not valid UTF-8"))?
+                .to_string(),
+        );
+    }
+
+    Ok(args)
+}
+
+/// Exception pip reports for conflicting requirements, which no other index
+/// can resolve. It is printed by class name, which is neither translated nor
+/// reworded between pip versions.
+const RESOLUTION_IMPOSSIBLE: &str = "ResolutionImpossible";
+
+/// Run pip against a local wheelhouse only, or against the PyPI mirrors in
+/// order, moving on to the next mirror when pip fails.
+///
+/// Failures that another index cannot fix are returned right away: conflicts
+/// between requirements, and for `pip install` anything after pip wrote its
+/// `--report`, as by then every package was resolved and fetched.
+async fn run_pip_with_index(
+    python_exe: &Path,
+    args: Vec<String>,
+    wheelhouse: Option<&Path>,
+    action: &str,
+) -> Result<()> {
+    if let Some(wheelhouse) = wheelhouse {
+        let mut args = args;
+        args.extend([
+            "--no-index".to_string(),
+            "--find-links".to_string(),
+            wheelhouse
+                .to_str()
+                .ok_or_else(|| AppError::io("wheelhouse path is not valid UTF-8"))?
+                .to_string(),
+        ]);
+        return run_pip(python_exe, &args, action).await;
+    }
+
+    // Only `pip install` supports `--report`.
+    let report_path = args
+        .iter()
+        .any(|arg| arg == "install")
+        .then(|| std::env::temp_dir().join(format!("pip-report-{}.json", uuid::Uuid::new_v4())));
+
+    let mut last_error = None;
+    for mirror in mirror_candidates(MirrorKind::PyPI) {
+        let mut mirror_args = args.clone();
+        // The official index is pip's default, which keeps user pip.conf settings.
         if !mirror.is_empty() {
-            args.push("-i".to_string());
-            args.push(mirror.to_string());
+            mirror_args.push("-i".to_string());
+            mirror_args.push(mirror.clone());
+        }
+        if let Some(report_path) = &report_path {
+            let _ = fs::remove_file(report_path);
+            mirror_args.push("--report".to_string());
+            mirror_args.push(report_path.to_string_lossy().into_owned());
+        }
+
+        let result = run_pip(python_exe, &mirror_args, action).await;
+        let resolved = report_path.as_ref().is_some_and(|path| path.exists());
+        if let Some(report_path) = &report_path {
+            let _ = fs::remove_file(report_path);
         }
+        match result {
+            Ok(()) => {
+                mark_mirror_healthy(MirrorKind::PyPI, &mirror);
+                return Ok(());
+            }
+            Err(e) if resolved || e.to_string().contains(RESOLUTION_IMPOSSIBLE) => return Err(e),
+            Err(e) => {
+                log::warn!(
+                    "pip failed with PyPI mirror {:?}, trying next: {}",
+                    mirror,
+                    e
+                );
+                mark_mirror_failed(MirrorKind::PyPI, &mirror);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| AppError::python(format!("Failed to {}", action))))
+}
+
+/// Number of pip processes currently running.
+static PIP_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a pip process in `PIP_RUNS` while alive.
+struct PipRun;
+
+impl PipRun {
+    fn start() -> Self {
+        PIP_RUNS.fetch_add(1, Ordering::SeqCst);
+        Self
     }
+}
+
+impl Drop for PipRun {
+    fn drop(&mut self) {
+        PIP_RUNS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
-    let output = Command::new(venv_python)
-        .args(&args)
+/// Whether a pip install is running and may be using the pip cache.
+pub fn pip_running() -> bool {
+    PIP_RUNS.load(Ordering::SeqCst) > 0
+}
+
+async fn run_pip(python_exe: &Path, args: &[String], action: &str) -> Result<()> {
+    let _running = PipRun::start();
+    let proxy_env = load_config()
+        .map(|config| pip_proxy_env(&config.proxy))
+        .unwrap_or_default();
+    let output = Command::new(python_exe)
+        .args(args)
+        .envs(proxy_env)
+        .env("PIP_CACHE_DIR", get_pip_cache_dir())
         .output()
         .await
-        .map_err(|e| AppError::python(format!("Failed to install requirements: {}", e)))?;
+        .map_err(|e| AppError::python(format!("Failed to {}: {}", action, e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(AppError::python(format!(
-            "Failed to install requirements: {}",
-            stderr
+            "Failed to {}: {}",
+            action, stderr
         )));
     }
 
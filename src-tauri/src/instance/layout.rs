@@ -0,0 +1,62 @@
+//! Instance directories that depend on the instance config.
+//!
+//! A dev-mode instance runs from a source checkout the launcher must never
+//! modify. Its code is only read through [`instance_code_dir`]; anything that
+//! extracts, replaces or cleans code uses [`get_instance_core_dir`], which is
+//! always the launcher-managed `core/`.
+//!
+//! [`get_instance_core_dir`]: crate::paths::get_instance_core_dir
+
+use std::path::PathBuf;
+
+use crate::config::{load_config, InstanceConfig};
+use crate::paths::{
+    get_instance_core_dir, get_instance_deploy_marker, get_instance_dir, get_instance_venv_dir,
+    get_venv_python,
+};
+
+fn instance_config(instance_id: &str) -> Option<InstanceConfig> {
+    load_config()
+        .ok()
+        .and_then(|config| config.instances.get(instance_id).cloned())
+}
+
+/// Get the directory AstrBot runs from: the source checkout of a dev-mode
+/// instance, otherwise the extracted `core/` directory.
+pub fn instance_code_dir(instance_id: &str) -> PathBuf {
+    match instance_config(instance_id).and_then(|instance| instance.source_dir) {
+        Some(source_dir) => PathBuf::from(source_dir),
+        None => get_instance_core_dir(instance_id),
+    }
+}
+
+/// Get the AstrBot root of an instance, the directory holding its `data/`.
+///
+/// Dev-mode instances with isolated data keep it in the instance directory
+/// rather than in their checkout.
+pub fn instance_root_dir(instance_id: &str) -> PathBuf {
+    let isolated = instance_config(instance_id)
+        .is_some_and(|instance| instance.source_dir.is_some() && instance.isolate_data);
+    if isolated {
+        get_instance_dir(instance_id)
+    } else {
+        instance_code_dir(instance_id)
+    }
+}
+
+/// Get the `data/` directory of an instance.
+pub fn instance_data_dir(instance_id: &str) -> PathBuf {
+    instance_root_dir(instance_id).join("data")
+}
+
+/// Check if an instance is fully deployed
+pub fn is_instance_deployed(instance_id: &str) -> bool {
+    let marker = get_instance_deploy_marker(instance_id);
+    if !marker.exists() {
+        return false;
+    }
+
+    let code_dir = instance_code_dir(instance_id);
+    let venv_python = get_venv_python(&get_instance_venv_dir(instance_id));
+    code_dir.join("main.py").exists() && venv_python.exists()
+}
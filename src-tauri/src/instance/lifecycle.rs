@@ -8,12 +8,12 @@ use tokio::process::Command;
 
 use super::crud::is_dashboard_enabled;
 use super::deploy::{deploy_instance, emit_progress};
+use super::layout::{instance_code_dir, instance_root_dir};
+use super::marker::{check_deploy_state, DeployState};
+use crate::component::tool_bin_dirs;
 use crate::config::load_config;
 use crate::error::{AppError, Result};
-use crate::paths::{
-    build_venv_path, get_instance_core_dir, get_instance_venv_dir, get_venv_python,
-    is_instance_deployed,
-};
+use crate::paths::{build_venv_path, get_instance_venv_dir, get_venv_python};
 use crate::process::{
     check_port_available, find_available_port, force_kill, graceful_shutdown, ProcessManager,
 };
@@ -33,9 +33,33 @@ pub async fn start_instance(
         return Err(AppError::instance_running());
     }
 
-    // Check if instance needs deployment
-    if !is_instance_deployed(instance_id) {
-        deploy_instance(instance_id, app_handle).await?;
+    let version = load_config()?
+        .instances
+        .get(instance_id)
+        .ok_or_else(|| AppError::instance_not_found(instance_id))?
+        .version
+        .clone();
+
+    // Check if instance needs deployment, or a redeploy because its environment changed
+    match check_deploy_state(instance_id, &version).await? {
+        DeployState::Deployed => {}
+        DeployState::NotDeployed => deploy_instance(instance_id, app_handle).await?,
+        DeployState::Stale(reason) => {
+            log::info!(
+                "Instance {} deployment is stale ({}), redeploying",
+                instance_id,
+                reason.describe()
+            );
+            if reason.requires_venv_rebuild() {
+                let venv_dir = get_instance_venv_dir(instance_id);
+                if venv_dir.exists() {
+                    std::fs::remove_dir_all(&venv_dir).map_err(|e| {
+                        AppError::io(format!("Failed to remove stale venv {:?}: {}", venv_dir, e))
+                    })?;
+                }
+            }
+            deploy_instance(instance_id, app_handle).await?;
+        }
     }
 
     // Check if dashboard is enabled
@@ -43,7 +67,8 @@ pub async fn start_instance(
 
     emit_progress(app_handle, instance_id, "start", "正在启动实例...", 95);
 
-    let core_dir = get_instance_core_dir(instance_id);
+    let core_dir = instance_code_dir(instance_id);
+    let root_dir = instance_root_dir(instance_id);
     let venv_dir = get_instance_venv_dir(instance_id);
     let venv_python = get_venv_python(&venv_dir);
 
@@ -66,11 +91,14 @@ pub async fn start_instance(
     }
 
     // Build command with environment variables
-    let path_with_venv = build_venv_path(&venv_python)?;
+    let tool_dirs = tool_bin_dirs(&instance_config.tools)?;
+    let path_with_venv = build_venv_path(&venv_python, &tool_dirs)?;
     let mut cmd = Command::new(&venv_python);
     cmd.arg(&main_py)
         .current_dir(&core_dir)
         .env("ASTRBOT_LAUNCHER", "1")
+        // AstrBot keeps data/ under ASTRBOT_ROOT, which defaults to the working dir.
+        .env("ASTRBOT_ROOT", &root_dir)
         .env("DASHBOARD_PORT", port.to_string())
         .env("PYTHONUNBUFFERED", "1")
         .env("PYTHONIOENCODING", "utf-8")
@@ -0,0 +1,174 @@
+//! Dependency manifest detection for AstrBot sources.
+//!
+//! Sources ship either a `requirements.txt` or a `pyproject.toml`, the latter
+//! optionally accompanied by a lock file (`uv.lock` or `pylock.toml`).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::checksum::sha256_file;
+use crate::error::{AppError, Result};
+
+/// Lock files understood when installing from pyproject.toml, in preference order.
+const LOCK_FILES: &[&str] = &["uv.lock", "pylock.toml"];
+
+/// The dependency manifest found in a source directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyManifest {
+    Requirements(PathBuf),
+    Pyproject {
+        pyproject: PathBuf,
+        lock: Option<PathBuf>,
+    },
+}
+
+impl DependencyManifest {
+    /// Files whose contents determine the installed dependencies.
+    fn files(&self) -> Vec<&Path> {
+        match self {
+            Self::Requirements(path) => vec![path.as_path()],
+            Self::Pyproject { pyproject, lock } => {
+                let mut files = vec![pyproject.as_path()];
+                files.extend(lock.as_deref());
+                files
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Pyproject {
+    #[serde(default)]
+    project: Option<PyprojectProject>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PyprojectProject {
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// `[[package]]` entries of uv.lock and `[[packages]]` entries of pylock.toml.
+#[derive(Debug, Default, Deserialize)]
+struct LockFile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+    #[serde(default)]
+    packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    /// uv.lock source table, e.g. `{ registry = "..." }` or `{ editable = "." }`.
+    #[serde(default)]
+    source: Option<toml::Value>,
+    /// pylock.toml non-index sources.
+    #[serde(default)]
+    directory: Option<toml::Value>,
+    #[serde(default)]
+    vcs: Option<toml::Value>,
+    #[serde(default)]
+    archive: Option<toml::Value>,
+}
+
+/// Find the dependency manifest of a source directory.
+///
+/// requirements.txt wins over pyproject.toml to keep existing releases unchanged.
+pub fn find_dependency_manifest(source_dir: &Path) -> Option<DependencyManifest> {
+    let requirements = source_dir.join("requirements.txt");
+    if requirements.is_file() {
+        return Some(DependencyManifest::Requirements(requirements));
+    }
+
+    let pyproject = source_dir.join("pyproject.toml");
+    if pyproject.is_file() {
+        let lock = LOCK_FILES
+            .iter()
+            .map(|name| source_dir.join(name))
+            .find(|path| path.is_file());
+        return Some(DependencyManifest::Pyproject { pyproject, lock });
+    }
+
+    None
+}
+
+/// Hash the dependency manifest files of a source directory, empty if there is none.
+pub fn hash_dependency_manifest(source_dir: &Path) -> Result<String> {
+    let Some(manifest) = find_dependency_manifest(source_dir) else {
+        return Ok(String::new());
+    };
+
+    let hashes = manifest
+        .files()
+        .into_iter()
+        .map(sha256_file)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(hashes.join(":"))
+}
+
+/// Read the `[project].dependencies` list from pyproject.toml.
+pub fn read_pyproject_dependencies(pyproject: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(pyproject)
+        .map_err(|e| AppError::io(format!("Failed to read {:?}: {}", pyproject, e)))?;
+    let parsed: Pyproject = toml::from_str(&content)
+        .map_err(|e| AppError::python(format!("Failed to parse pyproject.toml: {}", e)))?;
+
+    parsed
+        .project
+        .map(|project| project.dependencies)
+        .ok_or_else(|| AppError::python("pyproject.toml has no [project] table"))
+}
+
+/// Build `name==version` pip constraints from a lock file.
+///
+/// Only packages pinned from a registry are used. Packages locked at more than
+/// one version (platform-specific forks) are left to pip.
+pub fn read_lock_constraints(lock: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(lock)
+        .map_err(|e| AppError::io(format!("Failed to read {:?}: {}", lock, e)))?;
+    let parsed: LockFile = toml::from_str(&content)
+        .map_err(|e| AppError::python(format!("Failed to parse {:?}: {}", lock, e)))?;
+
+    let mut versions: HashMap<String, Option<String>> = HashMap::new();
+    for package in parsed.package.into_iter().chain(parsed.packages) {
+        if !is_registry_package(&package) {
+            continue;
+        }
+        let Some(version) = package.version else {
+            continue;
+        };
+        versions
+            .entry(package.name)
+            .and_modify(|existing| {
+                if existing.as_deref() != Some(version.as_str()) {
+                    *existing = None;
+                }
+            })
+            .or_insert(Some(version));
+    }
+
+    let mut constraints: Vec<String> = versions
+        .into_iter()
+        .filter_map(|(name, version)| version.map(|v| format!("{}=={}", name, v)))
+        .collect();
+    constraints.sort();
+    Ok(constraints)
+}
+
+/// uv.lock marks the project itself with an `editable`/`virtual` source;
+/// pylock.toml uses `directory`/`vcs`/`archive` for anything not from an index.
+fn is_registry_package(package: &LockedPackage) -> bool {
+    if package.directory.is_some() || package.vcs.is_some() || package.archive.is_some() {
+        return false;
+    }
+    match &package.source {
+        None => true,
+        Some(source) => source.get("registry").is_some(),
+    }
+}
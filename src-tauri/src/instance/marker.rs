@@ -0,0 +1,202 @@
+//! Deployment marker with an environment fingerprint.
+//!
+//! The marker records what a deployment was built against, so that a later
+//! start can detect a venv that no longer matches its surroundings (e.g. a
+//! reinstalled Python component or a moved data dir) and rebuild it.
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::layout::{instance_code_dir, is_instance_deployed};
+use super::manifest::hash_dependency_manifest;
+use crate::component::{
+    component_for_instance, get_python_for_instance, query_python_version, read_component_metadata,
+    registered_provider,
+};
+use crate::error::{AppError, Result};
+use crate::paths::{get_data_dir, get_instance_deploy_marker};
+
+/// Environment an instance venv was built against.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvFingerprint {
+    /// Component id of the base interpreter (e.g. "python312").
+    pub python_component: String,
+    /// Full interpreter version (e.g. "3.12.8").
+    pub python_version: String,
+    pub python_path: String,
+    /// SHA-256 of the installed runtime archive, empty if unknown.
+    #[serde(default)]
+    pub python_sha256: String,
+    /// SHA-256 of the core dependency manifest files, empty if absent.
+    pub requirements_hash: String,
+    pub data_dir: String,
+}
+
+/// Contents of the `.deployed` marker file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeployMarker {
+    pub version: String,
+    #[serde(default)]
+    pub launcher_version: String,
+    #[serde(default)]
+    pub deployed_at: String,
+    /// Missing for markers written by older launchers.
+    #[serde(default)]
+    pub fingerprint: Option<EnvFingerprint>,
+}
+
+/// Why an existing deployment no longer matches the current environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaleReason {
+    /// The launcher data dir moved; venv absolute paths are broken.
+    DataDir,
+    /// The base interpreter was replaced or changed version.
+    Interpreter,
+    /// The instance is configured for a different AstrBot version.
+    Version,
+    /// The core dependency manifest changed.
+    Requirements,
+}
+
+impl StaleReason {
+    /// Whether the venv has to be recreated from scratch.
+    pub fn requires_venv_rebuild(self) -> bool {
+        matches!(self, Self::DataDir | Self::Interpreter)
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::DataDir => "data directory moved",
+            Self::Interpreter => "python interpreter changed",
+            Self::Version => "version changed",
+            Self::Requirements => "requirements changed",
+        }
+    }
+}
+
+/// Deployment state of an instance compared with the current environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployState {
+    NotDeployed,
+    Deployed,
+    Stale(StaleReason),
+}
+
+/// Read the deployment marker, accepting the legacy `version=...` format.
+pub fn read_deploy_marker(instance_id: &str) -> Option<DeployMarker> {
+    let content = fs::read_to_string(get_instance_deploy_marker(instance_id)).ok()?;
+
+    if let Ok(marker) = toml::from_str::<DeployMarker>(&content) {
+        return Some(marker);
+    }
+
+    content.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("version=")
+            .map(|version| DeployMarker {
+                version: version.to_string(),
+                ..DeployMarker::default()
+            })
+    })
+}
+
+/// Write the deployment marker for a freshly deployed instance.
+pub fn write_deploy_marker(
+    instance_id: &str,
+    version: &str,
+    fingerprint: EnvFingerprint,
+) -> Result<()> {
+    let marker_path = get_instance_deploy_marker(instance_id);
+    if let Some(parent) = marker_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::io(format!("Failed to create marker directory: {}", e)))?;
+    }
+
+    let marker = DeployMarker {
+        version: version.to_string(),
+        launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+        deployed_at: chrono::Utc::now().to_rfc3339(),
+        fingerprint: Some(fingerprint),
+    };
+    let content = toml::to_string_pretty(&marker)?;
+    fs::write(&marker_path, content)
+        .map_err(|e| AppError::io(format!("Failed to write deployment marker: {}", e)))
+}
+
+/// Compute the fingerprint of the environment an instance would be deployed into now.
+pub async fn current_fingerprint(instance_id: &str, version: &str) -> Result<EnvFingerprint> {
+    let component = component_for_instance(instance_id, version)?;
+    let python_exe = get_python_for_instance(instance_id, version)?;
+    let python_version = query_python_version(&python_exe).await?;
+
+    Ok(EnvFingerprint {
+        python_component: component.dir_name().to_string(),
+        python_version,
+        python_path: python_exe.to_string_lossy().into_owned(),
+        // Provider interpreters are not installed from an archive.
+        python_sha256: match registered_provider(component) {
+            Some(_) => String::new(),
+            None => read_component_metadata(component)
+                .map(|metadata| metadata.sha256)
+                .unwrap_or_default(),
+        },
+        requirements_hash: hash_dependency_manifest(&instance_code_dir(instance_id))?,
+        data_dir: get_data_dir().to_string_lossy().into_owned(),
+    })
+}
+
+/// Compare the recorded deployment with the current environment.
+pub async fn check_deploy_state(instance_id: &str, version: &str) -> Result<DeployState> {
+    if !is_instance_deployed(instance_id) {
+        return Ok(DeployState::NotDeployed);
+    }
+    let Some(marker) = read_deploy_marker(instance_id) else {
+        return Ok(DeployState::NotDeployed);
+    };
+
+    let current = match marker.fingerprint {
+        Some(_) => Some(current_fingerprint(instance_id, version).await?),
+        None => None,
+    };
+
+    Ok(match stale_reason(&marker, version, current.as_ref()) {
+        Some(reason) => DeployState::Stale(reason),
+        None => DeployState::Deployed,
+    })
+}
+
+/// Pick the most severe mismatch between a marker and the current state.
+///
+/// Legacy markers without a fingerprint only have their version compared.
+fn stale_reason(
+    marker: &DeployMarker,
+    version: &str,
+    current: Option<&EnvFingerprint>,
+) -> Option<StaleReason> {
+    if let (Some(recorded), Some(current)) = (marker.fingerprint.as_ref(), current) {
+        if recorded.data_dir != current.data_dir {
+            return Some(StaleReason::DataDir);
+        }
+        if recorded.python_component != current.python_component
+            || recorded.python_path != current.python_path
+            || recorded.python_version != current.python_version
+            || recorded.python_sha256 != current.python_sha256
+        {
+            return Some(StaleReason::Interpreter);
+        }
+    }
+
+    if marker.version != version {
+        return Some(StaleReason::Version);
+    }
+
+    match (marker.fingerprint.as_ref(), current) {
+        (Some(recorded), Some(current))
+            if recorded.requirements_hash != current.requirements_hash =>
+        {
+            Some(StaleReason::Requirements)
+        }
+        _ => None,
+    }
+}
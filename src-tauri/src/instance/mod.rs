@@ -6,17 +6,35 @@
 //! - instances/{id}/venv/ - virtual environment for this instance
 //! - instances/{id}/core/data/ - instance data (including data/dist for webui)
 
+mod auto_update;
 mod cleanup;
 mod crud;
 mod deploy;
+mod layout;
 mod lifecycle;
+mod manifest;
+mod marker;
 mod types;
 
 // Re-export types
 pub use types::InstanceStatus;
 
 // Re-export CRUD operations
-pub use crud::{create_instance, delete_instance, list_instances, update_instance};
+pub use crud::{
+    create_dev_instance, create_instance, delete_instance, list_instances, set_instance_python,
+    set_instance_tools, update_instance,
+};
+
+// Re-export update checks
+pub use auto_update::{available_update, refresh_instance_updates, run_auto_updates};
+
+// Re-export deployment helpers
+pub use deploy::{
+    download_core_wheels, invalidate_version_deployments, pip_running, sync_plugin_requirements,
+};
+
+// Re-export config-dependent directories
+pub use layout::{instance_data_dir, instance_root_dir, is_instance_deployed};
 
 // Re-export lifecycle
 pub use lifecycle::{restart_instance, start_instance, stop_instance};
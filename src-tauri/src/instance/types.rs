@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::UpdateChannel;
+
 /// Status information for an instance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceStatus {
@@ -12,17 +14,45 @@ pub struct InstanceStatus {
     pub version: String,
     pub dashboard_enabled: bool,
     pub configured_port: u16,
+    /// Component id the instance runs on, after applying any override.
+    pub python_component: String,
+    /// Explicit `python_component` override from the instance config.
+    pub python_override: Option<String>,
+    /// Tool component ids put on the instance's PATH.
+    pub tools: Vec<String>,
+    /// Source checkout of a dev-mode instance.
+    pub source_dir: Option<String>,
+    /// Whether a dev-mode instance keeps `data/` out of its checkout.
+    pub isolate_data: bool,
+    /// Release channel the instance checks for updates.
+    pub update_channel: UpdateChannel,
+    /// Whether the instance is updated unattended at the scheduled hour.
+    pub auto_update: bool,
+    /// Newer release on the instance's channel, as of the last check.
+    pub update_available: Option<String>,
 }
 
 /// Deployment progress event payload.
 #[derive(Debug, Clone, Serialize)]
 pub struct DeployProgress {
     pub instance_id: String,
-    /// Step name: "extract", "venv", "deps", "start", "done", "error"
+    /// Step name: "extract", "venv", "deps", "plugin_deps", "start", "done", "error"
     pub step: String,
     pub message: String,
     /// Progress percentage: 0-100
     pub progress: u8,
+    /// Per-plugin dependency results, only filled on the "plugin_deps" step.
+    pub plugin_results: Vec<PluginDepsResult>,
+}
+
+/// Outcome of installing one plugin's requirements.txt.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginDepsResult {
+    /// Plugin directory name under `data/plugins`.
+    pub plugin: String,
+    pub success: bool,
+    /// Error detail when the install failed.
+    pub message: String,
 }
 
 /// Dashboard config from cmd_config.json.
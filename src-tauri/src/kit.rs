@@ -0,0 +1,390 @@
+//! Offline kits for setting up machines without network access.
+//!
+//! A kit is a tar.gz archive laid out as:
+//! - `kit.toml` - the [`KitManifest`]
+//! - `versions/{version}.zip` - AstrBot source archives
+//! - `components/{id}/` - unpacked Python components
+//! - `wheelhouses/{version}/` - wheels of each version's core dependencies
+
+use std::fs::{self, File};
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::archive::{extract_tar_gz_mapped, parse_entry_rel_path, read_zip_root_file};
+use crate::checksum::{sha256_file, verify_sha256};
+use crate::component::{
+    install_component_from_dir, read_component_metadata, registered_provider, remove_dir_logged,
+    required_component_for_version, ComponentId,
+};
+use crate::config::{load_config, AppConfig};
+use crate::download::{register_version, remove_version};
+use crate::error::{AppError, Result};
+use crate::instance::download_core_wheels;
+use crate::paths::{get_component_dir, get_data_dir, get_wheelhouse_dir, get_wheelhouses_dir};
+use crate::platform::get_arch_target;
+use crate::validation::resolve_version_zip_path;
+
+const KIT_MANIFEST: &str = "kit.toml";
+const KIT_FORMAT: u32 = 1;
+
+/// Contents of `kit.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KitManifest {
+    pub format: u32,
+    /// Platform the components and wheels were built for.
+    pub arch_target: String,
+    pub created_at: String,
+    pub launcher_version: String,
+    #[serde(default)]
+    pub versions: Vec<KitVersion>,
+    #[serde(default)]
+    pub components: Vec<KitComponent>,
+}
+
+/// An AstrBot version bundled in a kit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KitVersion {
+    pub version: String,
+    /// SHA-256 of the source archive.
+    pub sha256: String,
+    #[serde(default)]
+    pub requires_python: Option<String>,
+    /// Component the wheelhouse was downloaded with.
+    pub component: String,
+}
+
+/// A Python component bundled in a kit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KitComponent {
+    pub id: String,
+    pub version: String,
+}
+
+/// An installed version staged for export, with its wheelhouse downloaded.
+struct StagedVersion {
+    version: String,
+    zip_path: PathBuf,
+    requires_python: Option<String>,
+    component: ComponentId,
+}
+
+/// Pack the given versions, the Python components they need and their
+/// wheelhouses into an offline kit at `dest`.
+pub async fn export_offline_kit(versions: &[String], dest: &Path) -> Result<String> {
+    if versions.is_empty() {
+        return Err(AppError::other("No versions selected for the offline kit"));
+    }
+
+    let config = load_config()?;
+    let work_dir = get_data_dir().join("kit-export.tmp");
+    reset_dir(&work_dir)?;
+
+    let result = match stage_kit_versions(&config, versions, &work_dir).await {
+        // Hashing and packing a kit can take minutes; keep it off the async runtime.
+        Ok(staged) => {
+            let (work_dir, dest) = (work_dir.clone(), dest.to_path_buf());
+            tokio::task::spawn_blocking(move || build_kit(staged, &work_dir, &dest))
+                .await
+                .map_err(|e| AppError::other(format!("Offline kit export failed: {}", e)))
+                .and_then(|result| result)
+        }
+        Err(e) => Err(e),
+    };
+    remove_dir_logged(&work_dir);
+    if result.is_err() {
+        if let Err(e) = fs::remove_file(dest) {
+            log::debug!("Failed to remove partial kit {:?}: {}", dest, e);
+        }
+    }
+    result?;
+
+    log::info!("Exported offline kit with {:?} to {:?}", versions, dest);
+    Ok(dest.to_string_lossy().into_owned())
+}
+
+/// Check the versions can be exported and download their core wheels into
+/// `work_dir/wheelhouses`.
+async fn stage_kit_versions(
+    config: &AppConfig,
+    versions: &[String],
+    work_dir: &Path,
+) -> Result<Vec<StagedVersion>> {
+    let mut staged = Vec::with_capacity(versions.len());
+    for version in versions {
+        let installed = config
+            .installed_versions
+            .iter()
+            .find(|v| &v.version == version)
+            .ok_or_else(|| AppError::version_not_found(version))?;
+        let zip_path = PathBuf::from(&installed.zip_path);
+
+        // Only managed components can be bundled; a provider interpreter
+        // belongs to this machine.
+        let id = required_component_for_version(version)?;
+        let python_exe = id.spec().executable_path(&get_component_dir(id.dir_name()));
+        if registered_provider(id).is_some() || !python_exe.exists() {
+            return Err(AppError::python_not_installed());
+        }
+
+        let wheelhouse = work_dir.join("wheelhouses").join(version);
+        fs::create_dir_all(&wheelhouse)
+            .map_err(|e| AppError::io(format!("Failed to create wheelhouse: {}", e)))?;
+        download_core_wheels(
+            &python_exe,
+            &zip_path,
+            &work_dir.join("sources").join(version),
+            &wheelhouse,
+        )
+        .await?;
+
+        staged.push(StagedVersion {
+            version: version.clone(),
+            zip_path,
+            requires_python: installed.requires_python.clone(),
+            component: id,
+        });
+    }
+    Ok(staged)
+}
+
+fn build_kit(staged: Vec<StagedVersion>, work_dir: &Path, dest: &Path) -> Result<()> {
+    let mut manifest = KitManifest {
+        format: KIT_FORMAT,
+        arch_target: get_arch_target().map_err(AppError::other)?.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+        versions: Vec::new(),
+        components: Vec::new(),
+    };
+    let mut component_ids: Vec<ComponentId> = Vec::new();
+
+    for version in &staged {
+        manifest.versions.push(KitVersion {
+            version: version.version.clone(),
+            sha256: sha256_file(&version.zip_path)?,
+            requires_python: version.requires_python.clone(),
+            component: version.component.dir_name().to_string(),
+        });
+        if !component_ids.contains(&version.component) {
+            component_ids.push(version.component);
+        }
+    }
+
+    for id in &component_ids {
+        let metadata = read_component_metadata(*id).ok_or_else(|| {
+            AppError::other(format!(
+                "{} has no install metadata, reinstall it before exporting",
+                id.display_name()
+            ))
+        })?;
+        manifest.components.push(KitComponent {
+            id: id.dir_name().to_string(),
+            version: metadata.version,
+        });
+    }
+
+    let file = File::create(dest)
+        .map_err(|e| AppError::io(format!("Failed to create {:?}: {}", dest, e)))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    // Runtimes link their executables; keep the links instead of copies.
+    builder.follow_symlinks(false);
+
+    let manifest_toml = toml::to_string_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_toml.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, KIT_MANIFEST, manifest_toml.as_bytes())?;
+
+    for version in &staged {
+        builder.append_path_with_name(
+            &version.zip_path,
+            format!("versions/{}.zip", version.version),
+        )?;
+        builder.append_dir_all(
+            format!("wheelhouses/{}", version.version),
+            work_dir.join("wheelhouses").join(&version.version),
+        )?;
+    }
+    for id in component_ids {
+        builder.append_dir_all(
+            format!("components/{}", id.dir_name()),
+            get_component_dir(id.dir_name()),
+        )?;
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Read the manifest of an offline kit without extracting it.
+pub fn read_kit_manifest(archive_path: &Path) -> Result<KitManifest> {
+    let file = File::open(archive_path)
+        .map_err(|e| AppError::io(format!("Failed to open {:?}: {}", archive_path, e)))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.to_str() == Some(KIT_MANIFEST) {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            return toml::from_str(&content)
+                .map_err(|e| AppError::other(format!("Failed to parse {}: {}", KIT_MANIFEST, e)));
+        }
+    }
+
+    Err(AppError::other(format!(
+        "{:?} is not an offline kit: {} not found",
+        archive_path, KIT_MANIFEST
+    )))
+}
+
+/// Import an offline kit, registering its versions, components and
+/// wheelhouses as if they had been downloaded.
+pub fn import_offline_kit(archive_path: &Path) -> Result<String> {
+    let manifest = read_kit_manifest(archive_path)?;
+    if manifest.format != KIT_FORMAT {
+        return Err(AppError::other(format!(
+            "Unsupported offline kit format {}",
+            manifest.format
+        )));
+    }
+    let arch_target = get_arch_target().map_err(AppError::other)?;
+    if manifest.arch_target != arch_target {
+        return Err(AppError::other(format!(
+            "Offline kit is built for {}, this machine is {}",
+            manifest.arch_target, arch_target
+        )));
+    }
+
+    let work_dir = get_data_dir().join("kit-import.tmp");
+    reset_dir(&work_dir)?;
+    let result = install_kit(&manifest, archive_path, &work_dir);
+    remove_dir_logged(&work_dir);
+    result?;
+
+    log::info!(
+        "Imported offline kit {:?}: {} version(s), {} component(s)",
+        archive_path,
+        manifest.versions.len(),
+        manifest.components.len()
+    );
+    Ok(format!(
+        "已导入 {} 个版本和 {} 个组件",
+        manifest.versions.len(),
+        manifest.components.len()
+    ))
+}
+
+fn install_kit(manifest: &KitManifest, archive_path: &Path, work_dir: &Path) -> Result<()> {
+    extract_tar_gz_mapped(archive_path, work_dir, |raw_path| {
+        parse_entry_rel_path(raw_path).map(|relative| work_dir.join(relative))
+    })?;
+
+    // Validate everything before touching the installed state.
+    let mut components = Vec::with_capacity(manifest.components.len());
+    for component in &manifest.components {
+        let id = ComponentId::from_str_id(&component.id)
+            .ok_or_else(|| AppError::other(format!("Unknown component: {}", component.id)))?;
+        components.push((id, work_dir.join("components").join(&component.id)));
+    }
+
+    let config = load_config()?;
+    let mut versions = Vec::with_capacity(manifest.versions.len());
+    for kit_version in &manifest.versions {
+        let zip_path = resolve_version_zip_path(&kit_version.version)?;
+        let staged_zip = work_dir
+            .join("versions")
+            .join(format!("{}.zip", kit_version.version));
+        let sha256 = verify_sha256(&staged_zip, &kit_version.sha256)?;
+        // Replacing an installed archive would pull it from under its instances.
+        if let Some(installed) = config
+            .installed_versions
+            .iter()
+            .find(|v| v.version == kit_version.version)
+        {
+            let installed_sha256 = match &installed.sha256 {
+                Some(sha256) => sha256.clone(),
+                None => sha256_file(Path::new(&installed.zip_path))?,
+            };
+            if !installed_sha256.eq_ignore_ascii_case(&sha256) {
+                return Err(AppError::other(format!(
+                    "Version {} is already installed from a different archive, remove it before importing the offline kit",
+                    kit_version.version
+                )));
+            }
+            log::info!(
+                "Version {} is already installed, skipping it from offline kit",
+                kit_version.version
+            );
+            continue;
+        }
+        if read_zip_root_file(&staged_zip, "main.py")?.is_none() {
+            return Err(AppError::other(format!(
+                "{} in offline kit is not an AstrBot source archive",
+                kit_version.version
+            )));
+        }
+        versions.push((kit_version, staged_zip, zip_path));
+    }
+
+    // Versions go first so a failing component install can roll them back.
+    // Components already swapped in are complete installs and are kept.
+    let mut added = Vec::new();
+    let result = install_kit_versions(versions, work_dir, &mut added).and_then(|()| {
+        for (id, dir) in components {
+            let version = install_component_from_dir(id, &dir)?;
+            log::info!("Installed {} {} from offline kit", id.dir_name(), version);
+        }
+        Ok(())
+    });
+    if result.is_err() {
+        for version in added.iter().rev() {
+            log::info!("Rolling back version {} imported from offline kit", version);
+            if let Err(e) = remove_version(version) {
+                log::warn!("Failed to unregister version {}: {}", version, e);
+            }
+            remove_dir_logged(&get_wheelhouse_dir(version));
+        }
+    }
+    result
+}
+
+/// Move staged versions and their wheelhouses into place and register them,
+/// recording in `added` the versions registered so far.
+fn install_kit_versions(
+    versions: Vec<(&KitVersion, PathBuf, PathBuf)>,
+    work_dir: &Path,
+    added: &mut Vec<String>,
+) -> Result<()> {
+    fs::create_dir_all(get_wheelhouses_dir())
+        .map_err(|e| AppError::io(format!("Failed to create wheelhouses dir: {}", e)))?;
+    for (kit_version, staged_zip, zip_path) in versions {
+        let version = kit_version.version.as_str();
+        added.push(version.to_string());
+        fs::rename(&staged_zip, &zip_path)
+            .map_err(|e| AppError::io(format!("Failed to move {:?}: {}", staged_zip, e)))?;
+        register_version(version, &zip_path, None)?;
+
+        let staged_wheelhouse = work_dir.join("wheelhouses").join(version);
+        if staged_wheelhouse.is_dir() {
+            let wheelhouse = get_wheelhouse_dir(version);
+            remove_dir_logged(&wheelhouse);
+            fs::rename(&staged_wheelhouse, &wheelhouse).map_err(|e| {
+                AppError::io(format!("Failed to move {:?}: {}", staged_wheelhouse, e))
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+fn reset_dir(dir: &Path) -> Result<()> {
+    remove_dir_logged(dir);
+    fs::create_dir_all(dir).map_err(|e| AppError::io(format!("Failed to create {:?}: {}", dir, e)))
+}
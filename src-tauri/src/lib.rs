@@ -1,21 +1,32 @@
 mod archive;
 mod backup;
+mod changelog;
+mod checksum;
 mod commands;
 mod config;
+mod data_root;
+mod disk_usage;
 mod download;
 mod error;
 mod github;
 mod instance;
+mod kit;
+mod mirror;
 mod paths;
 mod platform;
+mod preflight;
 mod process;
+mod proxy;
+mod retention;
 mod component;
 mod validation;
 
 use std::sync::Arc;
 use std::time::Duration;
 
-use reqwest::Client;
+use chrono::Timelike as _;
+use tokio::time::MissedTickBehavior;
+
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::TrayIconBuilder;
 use tauri::Emitter as _;
@@ -31,6 +42,10 @@ use config::{load_config, with_config_mut};
 pub use error::{AppError, ErrorKind, Result};
 use instance::ProcessManager;
 
+const INSTANCE_UPDATE_INITIAL_DELAY: Duration = Duration::from_secs(60);
+const INSTANCE_UPDATE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DISK_USAGE_SCAN_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 #[allow(clippy::expect_used)]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -43,7 +58,10 @@ pub fn run() {
     paths::ensure_data_dirs().expect("Failed to create data directories");
     component::migrate_legacy_python_dirs();
 
-    let process_manager = Arc::new(ProcessManager::new());
+    let proxy_config = load_config()
+        .map(|config| config.proxy.clone())
+        .unwrap_or_default();
+    let process_manager = Arc::new(ProcessManager::new(&proxy_config));
     let pm_for_exit = Arc::clone(&process_manager);
     let pm_for_monitor = Arc::clone(&process_manager);
 
@@ -71,13 +89,7 @@ pub fn run() {
                 .build(),
         )
         .plugin(tauri_plugin_opener::init())
-        .manage(AppState {
-            client: Client::builder()
-                .timeout(Duration::from_secs(30))
-                .build()
-                .expect("Failed to create HTTP client"),
-            process_manager,
-        })
+        .manage(AppState::new(process_manager).expect("Failed to create HTTP client"))
         .setup(move |app| {
             #[cfg(target_os = "linux")]
             if let Some(main_webview) = app.get_webview_window("main") {
@@ -96,6 +108,8 @@ pub fn run() {
 
             pm_for_monitor.start_runtime_monitor();
             spawn_updater_check(app.handle().clone());
+            spawn_instance_update_checker(app.handle().clone());
+            spawn_disk_usage_scanner(app.handle().clone());
 
             let app_handle = app.handle().clone();
             let state: tauri::State<'_, AppState> = app.state();
@@ -118,6 +132,25 @@ pub fn run() {
                 }
             });
 
+            let download_handle = app.handle().clone();
+            let mut download_rx = download::download_manager().subscribe();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match download_rx.recv().await {
+                        Ok(progress) => {
+                            let _ = download_handle.emit("download-progress", &progress);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            log::debug!(
+                                "Download progress listener lagged, skipped {} events",
+                                skipped
+                            );
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+
             let show = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
             let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&show, &quit])?;
@@ -194,29 +227,60 @@ pub fn run() {
             commands::get_app_snapshot,
             commands::rebuild_app_snapshot,
             // Config
-            commands::save_github_proxy,
-            commands::save_pypi_mirror,
+            commands::save_github_proxies,
+            commands::save_github_token,
+            commands::save_source_repositories,
+            commands::save_pypi_mirrors,
+            commands::probe_mirrors,
+            commands::save_proxy_config,
             commands::save_close_to_tray,
             commands::compare_versions,
             commands::save_check_instance_update,
+            commands::save_auto_update_hour,
             commands::save_persist_instance_state,
             commands::is_macos,
             // Components
             commands::install_component,
             commands::reinstall_component,
+            commands::install_component_from_file,
+            commands::check_component_updates,
+            commands::update_component,
+            commands::discover_interpreters,
+            commands::register_component_provider,
+            commands::unregister_component_provider,
             // GitHub
             commands::fetch_releases,
+            commands::get_instance_changelog,
             // Version Management
             commands::install_version,
+            commands::install_version_from_ref,
+            commands::update_ref_version,
+            commands::import_version,
+            commands::export_offline_kit,
+            commands::import_offline_kit,
             commands::uninstall_version,
+            commands::save_retention_policy,
+            commands::preview_gc,
+            commands::run_gc,
+            commands::refresh_disk_usage,
+            commands::get_data_root,
+            commands::move_data_root,
+            // Downloads
+            commands::list_downloads,
+            commands::cancel_download,
             // Troubleshooting
             commands::clear_instance_data,
             commands::clear_instance_venv,
             commands::clear_pycache,
             // Instance Management
             commands::create_instance,
+            commands::create_dev_instance,
             commands::delete_instance,
             commands::update_instance,
+            commands::set_instance_python,
+            commands::set_instance_tools,
+            commands::set_instance_update_policy,
+            commands::check_instance_updates,
             commands::is_instance_deployed,
             commands::start_instance,
             commands::stop_instance,
@@ -255,6 +319,66 @@ fn spawn_updater_check(app: tauri::AppHandle) {
     });
 }
 
+/// Check instances for newer releases every hour and run unattended updates
+/// during the configured hour.
+fn spawn_instance_update_checker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Ticks stay on a fixed schedule however long each round takes.
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + INSTANCE_UPDATE_INITIAL_DELAY,
+            INSTANCE_UPDATE_INTERVAL,
+        );
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // Auto-updates run once a day, at the first check past the configured hour.
+        let mut last_auto_update: Option<chrono::NaiveDate> = None;
+        loop {
+            interval.tick().await;
+            let (client, pm) = {
+                let state: tauri::State<'_, AppState> = app.state();
+                (state.client(), Arc::clone(&state.process_manager))
+            };
+
+            match instance::refresh_instance_updates(&client).await {
+                Ok(_) => {
+                    let now = chrono::Local::now();
+                    let due = load_config()
+                        .is_ok_and(|c| now.hour() >= u32::from(c.auto_update_hour))
+                        && last_auto_update != Some(now.date_naive());
+                    if due {
+                        last_auto_update = Some(now.date_naive());
+                        if let Err(e) = instance::run_auto_updates(&client, &app, &pm).await {
+                            log::warn!("Instance auto-update failed: {e}");
+                        }
+                    }
+                }
+                Err(e) => log::warn!("Instance update check failed: {e}"),
+            }
+
+            if let Ok(snapshot) = commands::build_app_snapshot(&pm).await {
+                let _ = app.emit("app-snapshot", &snapshot);
+            }
+        }
+    });
+}
+
+/// Rescan disk usage periodically or on request and publish the result.
+fn spawn_disk_usage_scanner(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            disk_usage::scan().await;
+
+            let pm = {
+                let state: tauri::State<'_, AppState> = app.state();
+                Arc::clone(&state.process_manager)
+            };
+            if let Ok(snapshot) = commands::build_app_snapshot(&pm).await {
+                let _ = app.emit("app-snapshot", &snapshot);
+            }
+            disk_usage::wait_for_next_scan(DISK_USAGE_SCAN_INTERVAL).await;
+        }
+    });
+}
+
 // TODO: Better user experience around updates, e.g. non-blocking notification, background download, etc.
 async fn check_and_install_update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
     let Some(update) = app.updater()?.check().await? else {
@@ -0,0 +1,196 @@
+//! Ordered GitHub proxy and PyPI mirror lists with fallback.
+//!
+//! Mirrors are tried in the configured order, followed by the official
+//! address. A mirror that failed recently is moved behind the healthy ones
+//! until [`FAILURE_COOLDOWN`] has passed or a probe finds it reachable again.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use futures_util::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::config::load_config;
+use crate::download::check_url;
+use crate::error::{AppError, ErrorKind, Result};
+use crate::github::build_api_url;
+
+const OFFICIAL_PYPI: &str = "https://pypi.org";
+/// How long a failed mirror stays behind the healthy ones.
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(300);
+
+static MIRROR_FAILURES: OnceLock<RwLock<HashMap<(MirrorKind, String), Instant>>> = OnceLock::new();
+
+/// Which kind of source a mirror list serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorKind {
+    /// Prefix proxies wrapping GitHub API and download URLs.
+    GitHub,
+    /// PyPI simple index mirrors.
+    PyPI,
+}
+
+/// Result of probing a single mirror.
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorProbe {
+    /// Mirror URL, empty for the official address.
+    pub url: String,
+    pub available: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+fn failures() -> &'static RwLock<HashMap<(MirrorKind, String), Instant>> {
+    MIRROR_FAILURES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Trim, drop empty entries and duplicates, keeping the first occurrence.
+pub fn normalize_mirrors(mirrors: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for mirror in mirrors {
+        let mirror = mirror.trim().trim_end_matches('/');
+        if !mirror.is_empty() && !normalized.iter().any(|m| m == mirror) {
+            normalized.push(mirror.to_string());
+        }
+    }
+    normalized
+}
+
+/// Check that every mirror is an http(s) URL.
+pub fn validate_mirrors(mirrors: &[String]) -> Result<()> {
+    match mirrors
+        .iter()
+        .find(|m| !(m.starts_with("http://") || m.starts_with("https://")))
+    {
+        Some(invalid) => Err(AppError::config(format!(
+            "Mirror must be an http(s) URL: {}",
+            invalid
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn configured_mirrors(kind: MirrorKind) -> Vec<String> {
+    load_config()
+        .map(|config| match kind {
+            MirrorKind::GitHub => config.github_proxies.clone(),
+            MirrorKind::PyPI => config.pypi_mirrors.clone(),
+        })
+        .unwrap_or_default()
+}
+
+/// Mirrors to try, in order, with the official address (empty string) last.
+///
+/// Recently failed mirrors are moved to the end, keeping their relative order.
+pub fn mirror_candidates(kind: MirrorKind) -> Vec<String> {
+    let mut candidates = normalize_mirrors(&configured_mirrors(kind));
+    candidates.push(String::new());
+
+    let failures = failures().read().unwrap_or_else(|e| e.into_inner());
+    candidates.sort_by_key(|mirror| {
+        failures
+            .get(&(kind, mirror.clone()))
+            .is_some_and(|failed_at| failed_at.elapsed() < FAILURE_COOLDOWN)
+    });
+    candidates
+}
+
+/// Move a mirror behind the healthy ones, e.g. after pip could not reach it.
+pub fn mark_mirror_failed(kind: MirrorKind, mirror: &str) {
+    let mut failures = failures().write().unwrap_or_else(|e| e.into_inner());
+    failures.insert((kind, mirror.to_string()), Instant::now());
+}
+
+/// Clear a mirror's failure, e.g. after pip installed from it.
+pub fn mark_mirror_healthy(kind: MirrorKind, mirror: &str) {
+    let mut failures = failures().write().unwrap_or_else(|e| e.into_inner());
+    failures.remove(&(kind, mirror.to_string()));
+}
+
+/// PyPI simple index URL of a mirror, or of the official index when empty.
+fn pypi_index_url(mirror: &str) -> String {
+    if mirror.is_empty() {
+        format!("{}/simple", OFFICIAL_PYPI)
+    } else {
+        mirror.trim_end_matches('/').to_string()
+    }
+}
+
+fn probe_target(kind: MirrorKind, mirror: &str) -> String {
+    match kind {
+        MirrorKind::GitHub => build_api_url(mirror),
+        MirrorKind::PyPI => format!("{}/", pypi_index_url(mirror)),
+    }
+}
+
+/// Measure availability and response latency of a mirror.
+pub async fn probe_mirror(client: &Client, kind: MirrorKind, mirror: &str) -> MirrorProbe {
+    let started = Instant::now();
+    match check_url(client, &probe_target(kind, mirror)).await {
+        Ok(()) => {
+            mark_mirror_healthy(kind, mirror);
+            MirrorProbe {
+                url: mirror.to_string(),
+                available: true,
+                latency_ms: Some(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)),
+                error: None,
+            }
+        }
+        Err(e) => {
+            mark_mirror_failed(kind, mirror);
+            MirrorProbe {
+                url: mirror.to_string(),
+                available: false,
+                latency_ms: None,
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Probe `mirrors` and the official address concurrently, in list order.
+pub async fn probe_mirrors(
+    client: &Client,
+    kind: MirrorKind,
+    mirrors: &[String],
+) -> Vec<MirrorProbe> {
+    let mut targets = normalize_mirrors(mirrors);
+    targets.push(String::new());
+    join_all(
+        targets
+            .iter()
+            .map(|mirror| probe_mirror(client, kind, mirror)),
+    )
+    .await
+}
+
+/// Run `op` against each mirror candidate until one succeeds.
+///
+/// Network errors and checksum mismatches, which a misbehaving proxy can cause,
+/// move on to the next mirror; any other error is returned right away.
+pub async fn with_mirror_fallback<T, F, Fut>(kind: MirrorKind, mut op: F) -> Result<T>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut last_error = None;
+    for mirror in mirror_candidates(kind) {
+        match op(mirror.clone()).await {
+            Ok(value) => {
+                mark_mirror_healthy(kind, &mirror);
+                return Ok(value);
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::Network | ErrorKind::ChecksumMismatch) => {
+                log::warn!("{:?} mirror {:?} failed, trying next: {}", kind, mirror, e);
+                mark_mirror_failed(kind, &mirror);
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| AppError::network("No mirror available")))
+}
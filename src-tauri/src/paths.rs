@@ -5,13 +5,13 @@ use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::data_root::data_root;
 use crate::error::{AppError, Result};
 
-/// Get the root data directory for the application (~/.astrbot_launcher).
-#[allow(clippy::expect_used)]
+/// Get the root data directory for the application (~/.astrbot_launcher
+/// unless overridden, see [`crate::data_root`]).
 pub fn get_data_dir() -> PathBuf {
-    let home = dirs::home_dir().expect("Cannot find home directory");
-    home.join(".astrbot_launcher")
+    data_root().path.clone()
 }
 
 /// Get the path to the config file.
@@ -46,7 +46,10 @@ pub fn get_instance_deploy_marker(instance_id: &str) -> PathBuf {
     get_instance_dir(instance_id).join(".deployed")
 }
 
-/// Get the core directory for an instance.
+/// Get the launcher-managed code directory of an instance.
+///
+/// Dev-mode instances run from their checkout instead, see
+/// [`crate::instance::instance_code_dir`].
 pub fn get_instance_core_dir(instance_id: &str) -> PathBuf {
     get_instance_dir(instance_id).join("core")
 }
@@ -56,19 +59,6 @@ pub fn get_instance_venv_dir(instance_id: &str) -> PathBuf {
     get_instance_dir(instance_id).join("venv")
 }
 
-/// Check if an instance is fully deployed
-pub fn is_instance_deployed(instance_id: &str) -> bool {
-    let marker = get_instance_deploy_marker(instance_id);
-    if !marker.exists() {
-        return false;
-    }
-
-    let core_dir = get_instance_core_dir(instance_id);
-    let venv_dir = get_instance_venv_dir(instance_id);
-    let venv_python = get_venv_python(&venv_dir);
-    core_dir.join("main.py").exists() && venv_python.exists()
-}
-
 /// Get the versions directory.
 pub fn get_versions_dir() -> PathBuf {
     get_data_dir().join("versions")
@@ -79,6 +69,26 @@ pub fn get_version_zip_path(version: &str) -> PathBuf {
     get_versions_dir().join(format!("{}.zip", version))
 }
 
+/// Get the directory holding wheelhouses imported from offline kits.
+pub fn get_wheelhouses_dir() -> PathBuf {
+    get_data_dir().join("wheelhouses")
+}
+
+/// Get the wheelhouse for a specific version's core dependencies.
+pub fn get_wheelhouse_dir(version: &str) -> PathBuf {
+    get_wheelhouses_dir().join(version)
+}
+
+/// Get the directory caching GitHub API responses.
+pub fn get_github_cache_dir() -> PathBuf {
+    get_data_dir().join("cache").join("github")
+}
+
+/// Get the pip cache used when installing instance dependencies.
+pub fn get_pip_cache_dir() -> PathBuf {
+    get_data_dir().join("cache").join("pip")
+}
+
 /// Get the backups directory.
 pub fn get_backups_dir() -> PathBuf {
     get_data_dir().join("backups")
@@ -94,19 +104,6 @@ pub fn get_component_dir(dir_name: &str) -> PathBuf {
     get_components_dir().join(dir_name)
 }
 
-/// Get the path to the Python executable for a standalone Python directory.
-pub fn get_python_exe_path(python_dir: &Path) -> PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-        python_dir.join("python.exe")
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        python_dir.join("bin").join("python3")
-    }
-}
-
 /// Get the Python executable path within a virtual environment.
 pub fn get_venv_python(venv_dir: &Path) -> PathBuf {
     #[cfg(target_os = "windows")]
@@ -119,14 +116,18 @@ pub fn get_venv_python(venv_dir: &Path) -> PathBuf {
     }
 }
 
-/// Build PATH as `venv bin/scripts + existing PATH`.
-pub fn build_venv_path(venv_python: &Path) -> Result<OsString> {
+/// Build PATH as `venv bin/scripts + extra dirs + existing PATH`.
+pub fn build_venv_path(venv_python: &Path, extra_dirs: &[PathBuf]) -> Result<OsString> {
     let venv_bin = venv_python
         .parent()
         .ok_or_else(|| AppError::io("Invalid venv python path"))?;
     let mut paths = vec![venv_bin.to_path_buf()];
+    paths.extend(extra_dirs.iter().cloned());
     if let Some(existing_path) = env::var_os("PATH") {
-        paths.extend(env::split_paths(&existing_path).filter(|p| p.as_path() != venv_bin));
+        paths.extend(
+            env::split_paths(&existing_path)
+                .filter(|p| p.as_path() != venv_bin && !extra_dirs.contains(p)),
+        );
     }
     env::join_paths(paths).map_err(|e| AppError::io(format!("Failed to build venv PATH: {}", e)))
 }
@@ -1,7 +1,5 @@
 use std::env::consts::{ARCH, OS};
 
-use crate::github::GitHubAsset;
-
 pub fn get_arch_target() -> Result<&'static str, String> {
     match (OS, ARCH) {
         ("windows", "x86_64") => Ok("x86_64-pc-windows-msvc"),
@@ -15,36 +13,6 @@ pub fn get_arch_target() -> Result<&'static str, String> {
     }
 }
 
-/// Find a Python asset matching the given major version (e.g., "3.12" or "3.10").
-/// Returns (download_url, full_version) on success.
-pub fn find_python_asset_for_version(
-    assets: &[GitHubAsset],
-    major_version: &str,
-) -> Result<(String, String), String> {
-    let arch_target = get_arch_target()?;
-
-    // Pattern: cpython-{major_version}.XX+TAG-ARCH-install_only_stripped.tar.gz
-    let pattern_prefix = format!("cpython-{}", major_version);
-    let pattern_suffix = format!("{}-install_only_stripped.tar.gz", arch_target);
-
-    for asset in assets {
-        if asset.name.starts_with(&pattern_prefix) && asset.name.ends_with(&pattern_suffix) {
-            // Extract the full version (e.g., "3.12.8" from "cpython-3.12.8+...")
-            let version = asset
-                .name
-                .strip_prefix("cpython-")
-                .and_then(|s| s.split('+').next())
-                .unwrap_or(major_version);
-            return Ok((asset.browser_download_url.clone(), version.to_string()));
-        }
-    }
-
-    Err(format!(
-        "No Python {} asset found for platform {}",
-        major_version, arch_target
-    ))
-}
-
 pub fn is_macos() -> bool {
     OS == "macos"
 }
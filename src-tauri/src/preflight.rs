@@ -0,0 +1,73 @@
+//! Free-space checks before operations that write a lot.
+//!
+//! Running out of space halfway through an extraction or backup fails with
+//! a bare IO error and leaves partial files behind. Operations estimate what
+//! they need up front and fail early with `InsufficientDiskSpace` instead.
+
+use std::path::Path;
+
+use crate::error::{AppError, Result};
+
+/// How much larger extracted files are than their archive.
+const EXTRACT_EXPANSION_FACTOR: u64 = 4;
+
+/// Space left over after an operation, so the system is not filled to the brim.
+const RESERVED_SPACE: u64 = 100 * 1024 * 1024;
+
+/// Fail with `InsufficientDiskSpace` unless the filesystem holding `path` has
+/// `required` bytes free, plus a reserve.
+///
+/// `path` need not exist yet; its nearest existing ancestor is checked. If
+/// free space cannot be determined, the operation goes ahead.
+pub fn ensure_free_space(path: &Path, required: u64) -> Result<()> {
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return Ok(());
+    };
+    let available = match available_space(existing) {
+        Ok(available) => available,
+        Err(e) => {
+            log::warn!("Failed to query free space of {:?}: {}", existing, e);
+            return Ok(());
+        }
+    };
+
+    let needed = required.saturating_add(RESERVED_SPACE);
+    if available < needed {
+        return Err(AppError::insufficient_disk_space(
+            &existing.to_string_lossy(),
+            needed,
+            available,
+        ));
+    }
+    Ok(())
+}
+
+/// Estimated size of an archive's contents once extracted.
+pub fn extracted_size(archive_path: &Path) -> u64 {
+    std::fs::metadata(archive_path)
+        .map(|metadata| metadata.len().saturating_mul(EXTRACT_EXPANSION_FACTOR))
+        .unwrap_or_default()
+}
+
+/// Bytes available to the current user on the filesystem holding `path`.
+#[cfg(unix)]
+// Field widths of `statvfs` differ between platforms.
+#[allow(clippy::unnecessary_cast)]
+fn available_space(path: &Path) -> std::io::Result<u64> {
+    let stat = nix::sys::statvfs::statvfs(path)?;
+    Ok(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+}
+
+/// Bytes available to the current user on the filesystem holding `path`.
+#[cfg(windows)]
+fn available_space(path: &Path) -> std::io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt as _;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None) }
+        .map_err(std::io::Error::other)?;
+    Ok(available)
+}
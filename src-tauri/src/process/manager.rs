@@ -7,6 +7,9 @@ use std::time::{Duration, Instant};
 use reqwest::Client;
 use tokio::sync::broadcast;
 
+use crate::config::ProxyConfig;
+use crate::proxy::build_http_client;
+
 use super::control::{graceful_shutdown, is_process_alive};
 use super::health::check_health;
 #[cfg(target_os = "windows")]
@@ -24,11 +27,13 @@ pub struct ProcessManager {
 }
 
 impl ProcessManager {
+    /// Health checks only reach instances on localhost, which the proxy
+    /// always bypasses.
     #[allow(clippy::expect_used)]
-    pub fn new() -> Self {
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(3))
-            .build()
+    pub fn new(proxy: &ProxyConfig) -> Self {
+        let timeout = Duration::from_secs(3);
+        let http_client = build_http_client(timeout, proxy)
+            .or_else(|_| build_http_client(timeout, &ProxyConfig::default()))
             .expect("Failed to create HTTP client");
 
         let (runtime_events, _) = broadcast::channel(128);
@@ -375,6 +380,6 @@ impl ProcessManager {
 
 impl Default for ProcessManager {
     fn default() -> Self {
-        Self::new()
+        Self::new(&ProxyConfig::default())
     }
 }
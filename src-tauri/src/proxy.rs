@@ -0,0 +1,102 @@
+//! User-configured proxy for the launcher's HTTP clients and pip.
+
+use std::time::Duration;
+
+use reqwest::{Client, NoProxy, Proxy, Url};
+
+use crate::config::ProxyConfig;
+use crate::error::{AppError, Result};
+
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+/// Hosts that never go through the proxy, as instances are reached on them.
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+/// Proxy URL with the configured credentials filled in.
+fn proxy_url(proxy: &ProxyConfig) -> Result<Url> {
+    let mut url = Url::parse(proxy.url.trim())
+        .map_err(|e| AppError::config(format!("Invalid proxy URL {}: {}", proxy.url, e)))?;
+    if !PROXY_SCHEMES.contains(&url.scheme()) {
+        return Err(AppError::config(format!(
+            "Unsupported proxy scheme {}, expected one of {}",
+            url.scheme(),
+            PROXY_SCHEMES.join(", ")
+        )));
+    }
+
+    if !proxy.username.is_empty() {
+        let credentials = url
+            .set_username(&proxy.username)
+            .and_then(|()| url.set_password(Some(&proxy.password)));
+        if credentials.is_err() {
+            return Err(AppError::config(format!(
+                "Proxy URL {} cannot carry credentials",
+                proxy.url
+            )));
+        }
+    }
+
+    Ok(url)
+}
+
+/// Comma-separated bypass list: the configured entries plus local hosts.
+fn no_proxy_list(proxy: &ProxyConfig) -> String {
+    let mut entries: Vec<&str> = proxy
+        .no_proxy
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    for host in LOCAL_HOSTS {
+        if !entries.contains(host) {
+            entries.push(host);
+        }
+    }
+    entries.join(",")
+}
+
+/// Build an HTTP client routed through the configured proxy.
+///
+/// Without a proxy URL the client keeps reqwest's default of honouring the
+/// system proxy environment variables.
+pub fn build_http_client(timeout: Duration, proxy: &ProxyConfig) -> Result<Client> {
+    let mut builder = Client::builder().timeout(timeout);
+    if !proxy.url.trim().is_empty() {
+        let url = proxy_url(proxy)?;
+        let proxy = Proxy::all(url.as_str())
+            .map_err(|e| AppError::config(format!("Invalid proxy URL: {}", e)))?
+            .no_proxy(NoProxy::from_string(&no_proxy_list(proxy)));
+        builder = builder.proxy(proxy);
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::network(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Environment variables routing pip through the configured proxy.
+///
+/// pip only speaks SOCKS when PySocks is installed in the venv, so SOCKS
+/// proxies may not work for dependency installs.
+pub fn pip_proxy_env(proxy: &ProxyConfig) -> Vec<(&'static str, String)> {
+    if proxy.url.trim().is_empty() {
+        return Vec::new();
+    }
+    let url = match proxy_url(proxy) {
+        Ok(url) => url.to_string(),
+        Err(e) => {
+            log::warn!("Not passing proxy to pip: {}", e);
+            return Vec::new();
+        }
+    };
+    let no_proxy = no_proxy_list(proxy);
+
+    // requests prefers the lowercase variables on Unix, so set both.
+    vec![
+        ("HTTP_PROXY", url.clone()),
+        ("HTTPS_PROXY", url.clone()),
+        ("NO_PROXY", no_proxy.clone()),
+        ("http_proxy", url.clone()),
+        ("https_proxy", url),
+        ("no_proxy", no_proxy),
+    ]
+}
@@ -0,0 +1,328 @@
+//! Garbage collection of launcher data under the retention policy.
+//!
+//! A plan lists what would be removed and how much space it takes. Running
+//! the collection removes only items that were in the previewed plan and are
+//! still in a freshly computed one, so nothing the user has not seen is
+//! removed, and nothing that has since come back into use.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::backup::{delete_backup, list_backups};
+use crate::component::{is_component_locked, ComponentId};
+use crate::config::{load_config, AppConfig, RetentionPolicy};
+use crate::disk_usage::disk_size;
+use crate::download::{self, download_manager};
+use crate::error::{AppError, Result};
+use crate::instance::pip_running;
+use crate::paths::{
+    get_components_dir, get_pip_cache_dir, get_version_zip_path, get_versions_dir,
+    get_wheelhouses_dir,
+};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Archive names left behind by interrupted component installs.
+const COMPONENT_ARCHIVE_STEMS: &[&str] = &["component", "python"];
+const ARCHIVE_EXTENSIONS: &[&str] = &[".tar.gz", ".zip", ".gz"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GcItemKind {
+    /// An installed version no instance uses, outside the retention policy.
+    Version,
+    /// A version archive that is no longer listed as installed.
+    OrphanedArchive,
+    /// A wheelhouse of a version that is not installed.
+    Wheelhouse,
+    /// An auto-generated backup older than the retention period.
+    AutoBackup,
+    /// Partial downloads and archives of interrupted component installs.
+    Leftover,
+    /// The pip cache used for dependency installs.
+    PipCache,
+}
+
+/// Something garbage collection would remove.
+#[derive(Debug, Clone, Serialize)]
+pub struct GcItem {
+    pub kind: GcItemKind,
+    /// Version, backup file name or path, for display.
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcPlan {
+    pub items: Vec<GcItem>,
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcReport {
+    pub removed: Vec<GcItem>,
+    /// Bytes freed by the removed items.
+    pub reclaimed: u64,
+    /// Items that could not be removed, with the reason.
+    pub failed: Vec<String>,
+}
+
+/// List what garbage collection would remove under the current policy.
+pub fn plan_gc() -> Result<GcPlan> {
+    let config = load_config()?;
+    let policy = &config.retention;
+
+    let mut items = version_items(&config, policy);
+    let removed_versions: HashSet<&str> = items
+        .iter()
+        .filter(|item| item.kind == GcItemKind::Version)
+        .map(|item| item.name.as_str())
+        .collect();
+    let mut wheelhouses = wheelhouse_items(&config, &removed_versions);
+    items.append(&mut wheelhouses);
+    items.extend(orphaned_archive_items(&config));
+    items.extend(auto_backup_items(policy)?);
+    // Partial files of running downloads are still being written.
+    if !download_manager().has_active() {
+        items.extend(leftover_items());
+    }
+    let pip_cache = get_pip_cache_dir();
+    if policy.clear_pip_cache && pip_cache.exists() && !pip_running() {
+        items.push(item(GcItemKind::PipCache, "pip".to_string(), &pip_cache));
+    }
+
+    let total_size = items.iter().map(|item| item.size).sum();
+    Ok(GcPlan { items, total_size })
+}
+
+/// Remove the previewed items, given by path, that are still in the current
+/// plan, and report the space reclaimed.
+///
+/// Removal continues past individual failures, which are reported.
+pub fn run_gc(previewed: &[String]) -> Result<GcReport> {
+    let previewed: HashSet<&str> = previewed.iter().map(String::as_str).collect();
+    let plan = plan_gc()?;
+    let mut removed = Vec::new();
+    let mut failed = Vec::new();
+
+    for item in plan
+        .items
+        .into_iter()
+        .filter(|item| previewed.contains(item.path.as_str()))
+    {
+        match remove_item(&item) {
+            Ok(()) => removed.push(item),
+            Err(e) => {
+                log::warn!("Garbage collection failed to remove {}: {}", item.path, e);
+                failed.push(format!("{}: {}", item.name, e));
+            }
+        }
+    }
+
+    let reclaimed = removed.iter().map(|item| item.size).sum();
+    log::info!(
+        "Garbage collection removed {} items, reclaimed {} bytes",
+        removed.len(),
+        reclaimed
+    );
+    Ok(GcReport {
+        removed,
+        reclaimed,
+        failed,
+    })
+}
+
+fn remove_item(item: &GcItem) -> Result<()> {
+    match item.kind {
+        GcItemKind::Version => download::remove_version(&item.name),
+        GcItemKind::AutoBackup => delete_backup(&item.path),
+        _ => remove_path(Path::new(&item.path)),
+    }
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|e| AppError::io(format!("Failed to remove {:?}: {}", path, e)))
+}
+
+/// Installed versions outside the retention policy that no instance uses.
+fn version_items(config: &AppConfig, policy: &RetentionPolicy) -> Vec<GcItem> {
+    if policy.keep_latest_versions == 0 && policy.max_version_age_days == 0 {
+        return Vec::new();
+    }
+
+    let in_use: HashSet<&str> = config
+        .instances
+        .values()
+        .map(|instance| instance.version.as_str())
+        .collect();
+
+    // Most recently installed first. Versions from older launchers have no
+    // recorded install time and fall back to the archive modification time.
+    let mut versions: Vec<(&str, PathBuf, Option<SystemTime>)> = config
+        .installed_versions
+        .iter()
+        .map(|v| {
+            let path = get_version_zip_path(&v.version);
+            let installed = match v.installed_at.as_deref() {
+                Some(installed_at) => chrono::DateTime::parse_from_rfc3339(installed_at)
+                    .ok()
+                    .map(SystemTime::from),
+                None => fs::metadata(&path).and_then(|m| m.modified()).ok(),
+            };
+            (v.version.as_str(), path, installed)
+        })
+        .collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.2));
+
+    let max_age = days(policy.max_version_age_days);
+    versions
+        .into_iter()
+        .enumerate()
+        .filter(|(rank, (version, _, installed))| {
+            let within_count = (*rank as u64) < u64::from(policy.keep_latest_versions);
+            let within_age = max_age.is_some_and(|max_age| !is_older_than(*installed, max_age));
+            !in_use.contains(version) && !within_count && !within_age
+        })
+        .map(|(_, (version, path, _))| item(GcItemKind::Version, version.to_string(), &path))
+        .collect()
+}
+
+/// Wheelhouses of versions that are not installed or are being removed.
+fn wheelhouse_items(config: &AppConfig, removed_versions: &HashSet<&str>) -> Vec<GcItem> {
+    let installed: HashSet<&str> = config
+        .installed_versions
+        .iter()
+        .map(|v| v.version.as_str())
+        .filter(|v| !removed_versions.contains(v))
+        .collect();
+
+    dir_entries(&get_wheelhouses_dir())
+        .into_iter()
+        .filter(|(name, path)| path.is_dir() && !installed.contains(name.as_str()))
+        .map(|(name, path)| item(GcItemKind::Wheelhouse, name, &path))
+        .collect()
+}
+
+/// Version archives in `versions/` that no installed version points to.
+fn orphaned_archive_items(config: &AppConfig) -> Vec<GcItem> {
+    let installed: HashSet<PathBuf> = config
+        .installed_versions
+        .iter()
+        .map(|v| get_version_zip_path(&v.version))
+        .collect();
+
+    dir_entries(&get_versions_dir())
+        .into_iter()
+        .filter(|(name, path)| {
+            path.is_file() && name.ends_with(".zip") && !installed.contains(path)
+        })
+        .map(|(name, path)| item(GcItemKind::OrphanedArchive, name, &path))
+        .collect()
+}
+
+/// Auto-generated backups older than the retention period.
+fn auto_backup_items(policy: &RetentionPolicy) -> Result<Vec<GcItem>> {
+    let Some(max_age) = days(policy.auto_backup_max_age_days) else {
+        return Ok(Vec::new());
+    };
+    let cutoff = chrono::Utc::now()
+        - chrono::Duration::from_std(max_age).unwrap_or_else(|_| chrono::Duration::zero());
+
+    Ok(list_backups()?
+        .into_iter()
+        .filter(|backup| backup.metadata.auto_generated)
+        .filter(|backup| {
+            chrono::DateTime::parse_from_rfc3339(&backup.metadata.created_at)
+                .is_ok_and(|created| created < cutoff)
+        })
+        .map(|backup| {
+            item(
+                GcItemKind::AutoBackup,
+                backup.filename,
+                Path::new(&backup.path),
+            )
+        })
+        .collect())
+}
+
+/// Partial downloads and install archives left in the data directory.
+fn leftover_items() -> Vec<GcItem> {
+    let is_component_archive = |name: &str| {
+        COMPONENT_ARCHIVE_STEMS.iter().any(|stem| {
+            ARCHIVE_EXTENSIONS
+                .iter()
+                .any(|ext| name == format!("{}{}", stem, ext))
+        })
+    };
+
+    let mut candidates = dir_entries(&get_versions_dir());
+    for (name, component_dir) in dir_entries(&get_components_dir()) {
+        // An install in progress is still extracting its archive.
+        let id = name
+            .strip_suffix(".new")
+            .or_else(|| name.strip_suffix(".old"))
+            .unwrap_or(&name);
+        if ComponentId::from_str_id(id).is_some_and(is_component_locked) {
+            continue;
+        }
+        if component_dir.is_dir() {
+            candidates.extend(dir_entries(&component_dir));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(name, path)| {
+            path.is_file()
+                && (name.ends_with(".part")
+                    || name.ends_with(".part.validator")
+                    || is_component_archive(name))
+        })
+        .map(|(_, path)| {
+            let name = path.to_string_lossy().to_string();
+            item(GcItemKind::Leftover, name, &path)
+        })
+        .collect()
+}
+
+/// File names and paths of the entries of `dir`, empty if it cannot be read.
+fn dir_entries(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            Some((name, entry.path()))
+        })
+        .collect()
+}
+
+fn item(kind: GcItemKind, name: String, path: &Path) -> GcItem {
+    GcItem {
+        kind,
+        name,
+        path: path.to_string_lossy().to_string(),
+        size: disk_size(path),
+    }
+}
+
+fn days(count: u32) -> Option<Duration> {
+    (count > 0).then(|| Duration::from_secs(u64::from(count) * SECS_PER_DAY))
+}
+
+fn is_older_than(time: Option<SystemTime>, age: Duration) -> bool {
+    time.and_then(|time| time.elapsed().ok())
+        .is_some_and(|elapsed| elapsed > age)
+}
@@ -1,5 +1,21 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { GitHubRelease, AppSnapshot } from './types';
+import type {
+  GitHubRelease,
+  Changelog,
+  AppSnapshot,
+  GcPlan,
+  GcReport,
+  DataRoot,
+  ComponentUpdate,
+  DiscoveredInterpreter,
+  DownloadProgress,
+  MirrorKind,
+  MirrorProbe,
+  ProxyConfig,
+  RefKind,
+  RetentionPolicy,
+  UpdateChannel,
+} from './types';
 
 // Re-export types for convenience
 export type {
@@ -10,13 +26,40 @@ export type {
   InstalledVersion,
   GitHubRelease,
   GitHubAsset,
+  Changelog,
+  ReleaseNotes,
+  ChangelogSection,
   BackupMetadata,
   BackupInfo,
   DeployProgress,
   DeployStep,
+  PluginDepsResult,
   DeployType,
+  ComponentKind,
   ComponentStatus,
   ComponentsSnapshot,
+  ComponentProvider,
+  ComponentUpdate,
+  DiscoveredInterpreter,
+  DownloadProgress,
+  DownloadState,
+  MirrorKind,
+  MirrorProbe,
+  ProxyConfig,
+  RefKind,
+  RetentionPolicy,
+  GcItem,
+  GcItemKind,
+  GcPlan,
+  GcReport,
+  DiskUsage,
+  InstanceDiskUsage,
+  DataUsage,
+  EntrySize,
+  DataRoot,
+  DataRootSource,
+  VersionSource,
+  UpdateChannel,
 } from './types';
 
 export const api = {
@@ -29,12 +72,23 @@ export const api = {
   // ========================================
   // Config
   // ========================================
-  saveGithubProxy: (githubProxy: string) => invoke<void>('save_github_proxy', { githubProxy }),
-  savePypiMirror: (pypiMirror: string) => invoke<void>('save_pypi_mirror', { pypiMirror }),
+  saveGithubProxies: (githubProxies: string[]) =>
+    invoke<void>('save_github_proxies', { githubProxies }),
+  saveGithubToken: (githubToken: string) => invoke<void>('save_github_token', { githubToken }),
+  saveSourceRepositories: (sourceRepositories: string[]) =>
+    invoke<void>('save_source_repositories', { sourceRepositories }),
+  savePypiMirrors: (pypiMirrors: string[]) => invoke<void>('save_pypi_mirrors', { pypiMirrors }),
+  probeMirrors: (kind: MirrorKind, mirrors: string[]) =>
+    invoke<MirrorProbe[]>('probe_mirrors', { kind, mirrors }),
+  // A null password keeps the saved one
+  saveProxyConfig: (proxy: ProxyConfig, password: string | null) =>
+    invoke<void>('save_proxy_config', { proxy, password }),
   saveCloseToTray: (closeToTray: boolean) => invoke<void>('save_close_to_tray', { closeToTray }),
   compareVersions: (a: string, b: string) => invoke<number>('compare_versions', { a, b }),
   saveCheckInstanceUpdate: (checkInstanceUpdate: boolean) =>
     invoke<void>('save_check_instance_update', { checkInstanceUpdate }),
+  saveAutoUpdateHour: (autoUpdateHour: number) =>
+    invoke<void>('save_auto_update_hour', { autoUpdateHour }),
   savePersistInstanceState: (persistInstanceState: boolean) =>
     invoke<void>('save_persist_instance_state', { persistInstanceState }),
   isMacOS: () => invoke<boolean>('is_macos'),
@@ -45,17 +99,53 @@ export const api = {
   installComponent: (componentId: string) => invoke<string>('install_component', { componentId }),
   reinstallComponent: (componentId: string) =>
     invoke<string>('reinstall_component', { componentId }),
+  installComponentFromFile: (componentId: string, path: string) =>
+    invoke<string>('install_component_from_file', { componentId, path }),
+  checkComponentUpdates: () => invoke<ComponentUpdate[]>('check_component_updates'),
+  updateComponent: (componentId: string) => invoke<string>('update_component', { componentId }),
+  discoverInterpreters: () => invoke<DiscoveredInterpreter[]>('discover_interpreters'),
+  registerComponentProvider: (componentId: string, path: string) =>
+    invoke<DiscoveredInterpreter>('register_component_provider', { componentId, path }),
+  unregisterComponentProvider: (componentId: string) =>
+    invoke<void>('unregister_component_provider', { componentId }),
 
   // ========================================
   // GitHub
   // ========================================
   fetchReleases: () => invoke<GitHubRelease[]>('fetch_releases'),
+  getInstanceChangelog: (instanceId: string, target: string, includePrereleases?: boolean) =>
+    invoke<Changelog>('get_instance_changelog', {
+      instanceId,
+      target,
+      includePrereleases: includePrereleases ?? null,
+    }),
 
   // ========================================
   // Version Management
   // ========================================
   installVersion: (release: GitHubRelease) => invoke<void>('install_version', { release }),
+  installVersionFromRef: (repo: string, kind: RefKind, refName: string, version?: string) =>
+    invoke<string>('install_version_from_ref', { repo, kind, refName, version: version ?? null }),
+  updateRefVersion: (version: string) => invoke<string>('update_ref_version', { version }),
+  importVersion: (path: string, version?: string) =>
+    invoke<string>('import_version', { path, version: version ?? null }),
+  exportOfflineKit: (versions: string[], path: string) =>
+    invoke<string>('export_offline_kit', { versions, path }),
+  importOfflineKit: (path: string) => invoke<string>('import_offline_kit', { path }),
   uninstallVersion: (version: string) => invoke<void>('uninstall_version', { version }),
+  saveRetentionPolicy: (retention: RetentionPolicy) =>
+    invoke<void>('save_retention_policy', { retention }),
+  previewGc: () => invoke<GcPlan>('preview_gc'),
+  runGc: (paths: string[]) => invoke<GcReport>('run_gc', { paths }),
+  refreshDiskUsage: () => invoke<void>('refresh_disk_usage'),
+  getDataRoot: () => invoke<DataRoot>('get_data_root'),
+  moveDataRoot: (target: string) => invoke<void>('move_data_root', { target }),
+
+  // ========================================
+  // Downloads
+  // ========================================
+  listDownloads: () => invoke<DownloadProgress[]>('list_downloads'),
+  cancelDownload: (id: string) => invoke<boolean>('cancel_download', { id }),
 
   // ========================================
   // Troubleshooting
@@ -69,6 +159,8 @@ export const api = {
   // ========================================
   createInstance: (name: string, version: string, port: number = 0) =>
     invoke<void>('create_instance', { name, version, port }),
+  createDevInstance: (name: string, sourceDir: string, port: number, isolateData: boolean) =>
+    invoke<void>('create_dev_instance', { name, sourceDir, port, isolateData }),
   deleteInstance: (instanceId: string) => invoke<void>('delete_instance', { instanceId }),
   updateInstance: (instanceId: string, name?: string, version?: string, port?: number) =>
     invoke<void>('update_instance', {
@@ -77,6 +169,16 @@ export const api = {
       version: version ?? null,
       port: port ?? null,
     }),
+  setInstancePython: (instanceId: string, pythonComponent: string | null) =>
+    invoke<string | null>('set_instance_python', { instanceId, pythonComponent }),
+  setInstanceTools: (instanceId: string, tools: string[]) =>
+    invoke<void>('set_instance_tools', { instanceId, tools }),
+  setInstanceUpdatePolicy: (
+    instanceId: string,
+    updateChannel: UpdateChannel,
+    autoUpdate: boolean
+  ) => invoke<void>('set_instance_update_policy', { instanceId, updateChannel, autoUpdate }),
+  checkInstanceUpdates: () => invoke<Record<string, string>>('check_instance_updates'),
   isInstanceDeployed: (instanceId: string) =>
     invoke<boolean>('is_instance_deployed', { instanceId }),
   startInstance: (instanceId: string) => invoke<number>('start_instance', { instanceId }),
@@ -0,0 +1,97 @@
+import { useState, useEffect } from 'react';
+import { Modal, Alert, Collapse, Empty, Spin, Space, Tag, Typography } from 'antd';
+import { api } from '../api';
+import type { Changelog } from '../types';
+import { handleApiError } from '../utils';
+
+const { Paragraph, Text, Link } = Typography;
+
+interface ChangelogModalProps {
+  open: boolean;
+  instanceId: string | null;
+  target: string | null;
+  onCancel: () => void;
+}
+
+/** Release notes between an instance's version and a target version. */
+export function ChangelogModal({ open, instanceId, target, onCancel }: ChangelogModalProps) {
+  const [changelog, setChangelog] = useState<Changelog | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    if (!open || !instanceId || !target) return;
+    setChangelog(null);
+    setLoading(true);
+    api
+      .getInstanceChangelog(instanceId, target)
+      .then(setChangelog)
+      .catch(handleApiError)
+      .finally(() => setLoading(false));
+  }, [open, instanceId, target]);
+
+  return (
+    <Modal
+      title={changelog ? `更新日志: ${changelog.from} → ${changelog.to}` : '更新日志'}
+      open={open}
+      onCancel={onCancel}
+      footer={null}
+      width={720}
+      destroyOnHidden
+    >
+      <Spin spinning={loading}>
+        {changelog?.has_breaking_changes && (
+          <Alert
+            type="warning"
+            showIcon
+            message="包含不兼容变更，升级前请仔细阅读标记的内容"
+            style={{ marginBottom: 12 }}
+          />
+        )}
+        {changelog && !changelog.complete && (
+          <Alert
+            type="info"
+            showIcon
+            message="仅获取到最近的发布记录，更早版本的更新日志未列出"
+            style={{ marginBottom: 12 }}
+          />
+        )}
+        {changelog && changelog.releases.length === 0 && <Empty description="没有更新日志" />}
+        {changelog && changelog.releases.length > 0 && (
+          <Collapse
+            defaultActiveKey={changelog.releases.filter((r) => r.breaking).map((r) => r.tag_name)}
+            items={changelog.releases.map((release) => ({
+              key: release.tag_name,
+              label: (
+                <Space size={4}>
+                  <Text strong>{release.tag_name}</Text>
+                  {release.prerelease && <Tag color="orange">预发布</Tag>}
+                  {release.breaking && <Tag color="red">不兼容变更</Tag>}
+                </Space>
+              ),
+              extra: (
+                <Link href={release.html_url} target="_blank" onClick={(e) => e.stopPropagation()}>
+                  查看发布页
+                </Link>
+              ),
+              children:
+                release.sections.length === 0 ? (
+                  <Text type="secondary">无发布说明</Text>
+                ) : (
+                  release.sections.map((section, index) => (
+                    <div key={index}>
+                      {section.heading && (
+                        <Text strong type={section.breaking ? 'danger' : undefined}>
+                          {section.heading}
+                        </Text>
+                      )}
+                      <Paragraph style={{ whiteSpace: 'pre-wrap' }}>{section.body}</Paragraph>
+                    </div>
+                  ))
+                ),
+            }))}
+          />
+        )}
+      </Spin>
+    </Modal>
+  );
+}
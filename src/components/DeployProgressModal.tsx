@@ -62,6 +62,17 @@ export function DeployProgressModal({
           >
             {progress.message}
           </Text>
+          {progress.plugin_results
+            .filter((result) => !result.success)
+            .map((result) => (
+              <Text
+                key={result.plugin}
+                type="warning"
+                style={{ display: 'block', marginTop: 4, textAlign: 'center' }}
+              >
+                插件 {result.plugin} 依赖安装失败
+              </Text>
+            ))}
         </>
       )}
     </Modal>
@@ -0,0 +1,89 @@
+import { Button, Descriptions, Space, Table, Tooltip, Typography } from 'antd';
+import { ReloadOutlined } from '@ant-design/icons';
+import type { DiskUsage, EntrySize, InstanceDiskUsage, InstanceStatus } from '../types';
+import { formatBytes } from '../utils';
+
+const { Text } = Typography;
+
+interface DiskUsagePanelProps {
+  usage: DiskUsage | null;
+  instances: InstanceStatus[];
+  onRefresh: () => void;
+}
+
+function sum(entries: EntrySize[]): number {
+  return entries.reduce((total, entry) => total + entry.size, 0);
+}
+
+/** Where the launcher's disk space goes, from the background scan. */
+export function DiskUsagePanel({ usage, instances, onRefresh }: DiskUsagePanelProps) {
+  const instanceName = (id: string) => instances.find((i) => i.id === id)?.name ?? id;
+  const scannedAt = usage?.scanned_at ? new Date(usage.scanned_at).toLocaleString() : null;
+
+  return (
+    <>
+      <Space style={{ marginBottom: 12 }}>
+        <Text strong>
+          总占用: {usage?.scanned_at ? formatBytes(usage.total) : '统计中...'}
+        </Text>
+        <Text type="secondary">{scannedAt && `统计于 ${scannedAt}`}</Text>
+        <Button
+          size="small"
+          icon={<ReloadOutlined />}
+          loading={usage?.scanning}
+          onClick={onRefresh}
+        >
+          重新统计
+        </Button>
+      </Space>
+
+      {usage && (
+        <>
+          <Descriptions size="small" column={3} style={{ marginBottom: 12 }}>
+            <Descriptions.Item label="版本">{formatBytes(sum(usage.versions))}</Descriptions.Item>
+            <Descriptions.Item label="备份">{formatBytes(sum(usage.backups))}</Descriptions.Item>
+            <Descriptions.Item label="运行时组件">
+              {formatBytes(sum(usage.components))}
+            </Descriptions.Item>
+            <Descriptions.Item label="离线依赖包">
+              {formatBytes(usage.wheelhouses)}
+            </Descriptions.Item>
+            <Descriptions.Item label="缓存">{formatBytes(usage.cache)}</Descriptions.Item>
+          </Descriptions>
+
+          <Table<InstanceDiskUsage>
+            size="small"
+            rowKey="id"
+            pagination={false}
+            dataSource={usage.instances}
+            columns={[
+              { title: '实例', dataIndex: 'id', ellipsis: true, render: instanceName },
+              { title: '代码', dataIndex: 'core', render: formatBytes },
+              { title: '虚拟环境', dataIndex: 'venv', render: formatBytes },
+              {
+                title: '数据',
+                dataIndex: 'data',
+                render: (data: InstanceDiskUsage['data']) => (
+                  <Tooltip
+                    title={
+                      <>
+                        <div>插件: {formatBytes(data.plugins)}</div>
+                        <div>日志: {formatBytes(data.logs)}</div>
+                        <div>临时文件: {formatBytes(data.temp)}</div>
+                        <div>知识库: {formatBytes(data.knowledge_base)}</div>
+                        <div>其他: {formatBytes(data.other)}</div>
+                      </>
+                    }
+                  >
+                    <span>{formatBytes(data.total)}</span>
+                  </Tooltip>
+                ),
+              },
+              { title: '合计', dataIndex: 'total', render: formatBytes },
+            ]}
+          />
+        </>
+      )}
+    </>
+  );
+}
@@ -0,0 +1,65 @@
+import { Button, Progress, Space, Tooltip, Typography } from 'antd';
+import { CloseOutlined } from '@ant-design/icons';
+import type { DownloadProgress } from '../types';
+import { formatBytes } from '../utils';
+
+const { Text } = Typography;
+
+interface DownloadProgressBarProps {
+  progress: DownloadProgress;
+  onCancel: (id: string) => void;
+}
+
+function formatEta(secs: number): string {
+  if (secs < 60) return `${secs} 秒`;
+  const minutes = Math.floor(secs / 60);
+  return `${minutes} 分 ${secs % 60} 秒`;
+}
+
+function statusText(progress: DownloadProgress): string {
+  switch (progress.state) {
+    case 'queued':
+      return '排队中';
+    case 'retrying':
+      return '连接中断，等待重试';
+    default: {
+      const size = progress.total
+        ? `${formatBytes(progress.downloaded)} / ${formatBytes(progress.total)}`
+        : formatBytes(progress.downloaded);
+      const speed = progress.speed > 0 ? ` · ${formatBytes(progress.speed)}/s` : '';
+      const eta = progress.eta_secs !== null ? ` · 剩余 ${formatEta(progress.eta_secs)}` : '';
+      return `${size}${speed}${eta}`;
+    }
+  }
+}
+
+export function DownloadProgressBar({ progress, onCancel }: DownloadProgressBarProps) {
+  const percent = progress.total
+    ? Math.floor((progress.downloaded / progress.total) * 100)
+    : undefined;
+
+  return (
+    <Space orientation="vertical" size={0} style={{ width: '100%' }}>
+      <Space style={{ width: '100%' }}>
+        <Progress
+          percent={percent ?? 0}
+          status={progress.state === 'downloading' ? 'active' : 'normal'}
+          showInfo={percent !== undefined}
+          size="small"
+          style={{ width: 240, margin: 0 }}
+        />
+        <Tooltip title="取消下载">
+          <Button
+            type="text"
+            size="small"
+            icon={<CloseOutlined />}
+            onClick={() => onCancel(progress.id)}
+          />
+        </Tooltip>
+      </Space>
+      <Text type="secondary" style={{ fontSize: 12 }}>
+        {statusText(progress)}
+      </Text>
+    </Space>
+  );
+}
@@ -0,0 +1,79 @@
+import { Modal, Table, Tag, Typography, Empty } from 'antd';
+import type { GcItem, GcItemKind, GcPlan } from '../types';
+import { formatBytes } from '../utils';
+
+const { Paragraph, Text } = Typography;
+
+const KIND_LABELS: Record<GcItemKind, string> = {
+  version: '未使用的版本',
+  orphaned_archive: '孤立的版本压缩包',
+  wheelhouse: '离线依赖包',
+  auto_backup: '过期的自动备份',
+  leftover: '残留的下载文件',
+  pip_cache: 'pip 缓存',
+};
+
+interface GcPreviewModalProps {
+  open: boolean;
+  plan: GcPlan | null;
+  loading?: boolean;
+  onConfirm: () => void;
+  onCancel: () => void;
+}
+
+/** Show what garbage collection would remove before running it. */
+export function GcPreviewModal({
+  open,
+  plan,
+  loading = false,
+  onConfirm,
+  onCancel,
+}: GcPreviewModalProps) {
+  const empty = !plan || plan.items.length === 0;
+
+  return (
+    <Modal
+      title="清理存储空间"
+      open={open}
+      onCancel={onCancel}
+      onOk={onConfirm}
+      okText="清理"
+      okButtonProps={{ danger: true, disabled: empty, loading }}
+      width={720}
+      destroyOnHidden
+    >
+      {!plan || plan.items.length === 0 ? (
+        <Empty description="没有可清理的内容" />
+      ) : (
+        <>
+          <Paragraph>
+            以下内容将被删除，共可释放 <Text strong>{formatBytes(plan.total_size)}</Text>
+            。正在使用的版本不会被删除。
+          </Paragraph>
+          <Table<GcItem>
+            size="small"
+            rowKey="path"
+            pagination={false}
+            scroll={{ y: 320 }}
+            dataSource={plan.items}
+            columns={[
+              {
+                title: '类型',
+                dataIndex: 'kind',
+                width: 150,
+                render: (kind: GcItemKind) => <Tag>{KIND_LABELS[kind]}</Tag>,
+              },
+              { title: '名称', dataIndex: 'name', ellipsis: true },
+              {
+                title: '大小',
+                dataIndex: 'size',
+                width: 100,
+                render: (size: number) => formatBytes(size),
+              },
+            ]}
+          />
+        </>
+      )}
+    </Modal>
+  );
+}
@@ -0,0 +1,102 @@
+import { useState, useEffect } from 'react';
+import { Modal, List, Tag, Input, Space, Typography, Button } from 'antd';
+import { api } from '../api';
+import type { ComponentStatus, DiscoveredInterpreter } from '../types';
+import { handleApiError } from '../utils';
+
+const { Text } = Typography;
+
+const SOURCE_LABELS: Record<DiscoveredInterpreter['source'], string> = {
+  path: 'PATH',
+  pyenv: 'pyenv',
+  prefix: '系统目录',
+  manual: '手动',
+};
+
+interface InterpreterPickerModalProps {
+  component: ComponentStatus | null;
+  loading?: boolean;
+  onSelect: (path: string) => void;
+  onCancel: () => void;
+}
+
+/** Pick an existing interpreter to provide a Python component. */
+export function InterpreterPickerModal({
+  component,
+  loading = false,
+  onSelect,
+  onCancel,
+}: InterpreterPickerModalProps) {
+  const [interpreters, setInterpreters] = useState<DiscoveredInterpreter[]>([]);
+  const [discovering, setDiscovering] = useState(false);
+  const [manualPath, setManualPath] = useState('');
+
+  useEffect(() => {
+    if (!component) return;
+    setManualPath('');
+    setDiscovering(true);
+    api
+      .discoverInterpreters()
+      .then(setInterpreters)
+      .catch(handleApiError)
+      .finally(() => setDiscovering(false));
+  }, [component]);
+
+  return (
+    <Modal
+      title={component ? `为 ${component.display_name} 选择已有解释器` : ''}
+      open={component !== null}
+      onCancel={onCancel}
+      footer={null}
+      destroyOnHidden
+    >
+      <List
+        loading={discovering}
+        dataSource={interpreters}
+        locale={{ emptyText: '未发现可用的 Python 解释器' }}
+        renderItem={(item) => (
+          <List.Item
+            actions={[
+              <Button
+                key="use"
+                size="small"
+                disabled={!item.has_venv}
+                loading={loading}
+                onClick={() => onSelect(item.path)}
+              >
+                使用
+              </Button>,
+            ]}
+          >
+            <List.Item.Meta
+              title={
+                <Space>
+                  Python {item.version}
+                  <Tag>{item.arch}</Tag>
+                  <Tag>{SOURCE_LABELS[item.source]}</Tag>
+                  {!item.has_venv && <Tag color="red">缺少 venv</Tag>}
+                </Space>
+              }
+              description={<Text type="secondary">{item.path}</Text>}
+            />
+          </List.Item>
+        )}
+      />
+      <Space.Compact style={{ width: '100%', marginTop: 16 }}>
+        <Input
+          placeholder="或输入解释器路径"
+          value={manualPath}
+          onChange={(e) => setManualPath(e.target.value)}
+        />
+        <Button
+          type="primary"
+          disabled={!manualPath.trim()}
+          loading={loading}
+          onClick={() => onSelect(manualPath.trim())}
+        >
+          使用
+        </Button>
+      </Space.Compact>
+    </Modal>
+  );
+}
@@ -0,0 +1,84 @@
+import { useState } from 'react';
+import { Button, Input, List, Space, Tag, Tooltip } from 'antd';
+import { SaveOutlined, ThunderboltOutlined } from '@ant-design/icons';
+import { api } from '../api';
+import type { MirrorKind, MirrorProbe } from '../types';
+import { handleApiError } from '../utils';
+
+interface MirrorListFieldProps {
+  kind: MirrorKind;
+  value: string;
+  onChange: (value: string) => void;
+  onSave: () => void;
+  saving: boolean;
+  placeholder: string;
+}
+
+/** Split a one-URL-per-line text into an ordered mirror list. */
+export function parseMirrorList(text: string): string[] {
+  return text
+    .split('\n')
+    .map((line) => line.trim())
+    .filter((line) => line.length > 0);
+}
+
+export function MirrorListField({
+  kind,
+  value,
+  onChange,
+  onSave,
+  saving,
+  placeholder,
+}: MirrorListFieldProps) {
+  const [probing, setProbing] = useState(false);
+  const [probes, setProbes] = useState<MirrorProbe[]>([]);
+
+  const handleProbe = async () => {
+    setProbing(true);
+    try {
+      setProbes(await api.probeMirrors(kind, parseMirrorList(value)));
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      setProbing(false);
+    }
+  };
+
+  return (
+    <Space orientation="vertical" style={{ width: '100%' }}>
+      <Input.TextArea
+        value={value}
+        onChange={(e) => onChange(e.target.value)}
+        placeholder={placeholder}
+        autoSize={{ minRows: 2, maxRows: 6 }}
+      />
+      <Space>
+        <Button icon={<ThunderboltOutlined />} loading={probing} onClick={handleProbe}>
+          测速
+        </Button>
+        <Button icon={<SaveOutlined />} loading={saving} onClick={onSave}>
+          保存
+        </Button>
+      </Space>
+      {probes.length > 0 && (
+        <List
+          size="small"
+          bordered
+          dataSource={probes}
+          renderItem={(probe) => (
+            <List.Item>
+              <span>{probe.url || '官方地址'}</span>
+              {probe.available ? (
+                <Tag color="green">{probe.latency_ms} ms</Tag>
+              ) : (
+                <Tooltip title={probe.error}>
+                  <Tag color="red">不可用</Tag>
+                </Tooltip>
+              )}
+            </List.Item>
+          )}
+        />
+      )}
+    </Space>
+  );
+}
@@ -0,0 +1,49 @@
+import { useState, useEffect } from 'react';
+import { Modal, Checkbox, Typography } from 'antd';
+import type { InstalledVersion } from '../types';
+
+const { Paragraph } = Typography;
+
+interface OfflineKitExportModalProps {
+  open: boolean;
+  versions: InstalledVersion[];
+  loading?: boolean;
+  onExport: (versions: string[]) => void;
+  onCancel: () => void;
+}
+
+/** Choose the versions to pack into an offline kit. */
+export function OfflineKitExportModal({
+  open,
+  versions,
+  loading = false,
+  onExport,
+  onCancel,
+}: OfflineKitExportModalProps) {
+  const [selected, setSelected] = useState<string[]>([]);
+
+  useEffect(() => {
+    if (open) setSelected([]);
+  }, [open]);
+
+  return (
+    <Modal
+      title="导出离线包"
+      open={open}
+      onCancel={onCancel}
+      onOk={() => onExport(selected)}
+      okText="导出"
+      okButtonProps={{ disabled: selected.length === 0, loading }}
+      destroyOnHidden
+    >
+      <Paragraph type="secondary">
+        离线包包含所选版本、所需的 Python 运行时及依赖 wheel，可在无网络的机器上导入。
+      </Paragraph>
+      <Checkbox.Group
+        value={selected}
+        onChange={(values) => setSelected(values as string[])}
+        options={versions.map((v) => ({ label: v.version, value: v.version }))}
+      />
+    </Modal>
+  );
+}
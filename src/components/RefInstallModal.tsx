@@ -0,0 +1,91 @@
+import { useState, useEffect } from 'react';
+import { Modal, Form, Input, Select, Radio, Typography } from 'antd';
+import type { RefKind } from '../types';
+
+const { Paragraph } = Typography;
+
+const OFFICIAL_REPO = 'AstrBotDevs/AstrBot';
+
+const REF_LABELS: Record<RefKind, string> = { branch: '分支', tag: '标签', commit: '提交 SHA' };
+const REF_PLACEHOLDERS: Record<RefKind, string> = {
+  branch: '例如: master',
+  tag: '例如: v4.0.0',
+  commit: '例如: 1a2b3c4',
+};
+
+interface RefInstallModalProps {
+  open: boolean;
+  repositories: string[];
+  loading?: boolean;
+  onInstall: (repo: string, kind: RefKind, refName: string, version?: string) => void;
+  onCancel: () => void;
+}
+
+/** Install a version from a tag, branch or commit of a source repository. */
+export function RefInstallModal({
+  open,
+  repositories,
+  loading = false,
+  onInstall,
+  onCancel,
+}: RefInstallModalProps) {
+  const [repo, setRepo] = useState(OFFICIAL_REPO);
+  const [kind, setKind] = useState<RefKind>('branch');
+  const [refName, setRefName] = useState('');
+  const [version, setVersion] = useState('');
+
+  useEffect(() => {
+    if (open) {
+      setRepo(OFFICIAL_REPO);
+      setKind('branch');
+      setRefName('');
+      setVersion('');
+    }
+  }, [open]);
+
+  const repoOptions = [OFFICIAL_REPO, ...repositories.filter((r) => r !== OFFICIAL_REPO)].map(
+    (r) => ({ label: r, value: r })
+  );
+
+  return (
+    <Modal
+      title="从源码仓库安装"
+      open={open}
+      onCancel={onCancel}
+      onOk={() => onInstall(repo, kind, refName.trim(), version.trim() || undefined)}
+      okText="安装"
+      okButtonProps={{ disabled: refName.trim().length === 0, loading }}
+      destroyOnHidden
+    >
+      <Paragraph type="secondary">
+        安装时会记录引用对应的提交。从分支安装的版本之后可以更新到分支的最新提交。
+      </Paragraph>
+      <Form layout="vertical">
+        <Form.Item label="仓库" extra="可在高级设置中添加其他仓库">
+          <Select value={repo} onChange={setRepo} options={repoOptions} />
+        </Form.Item>
+        <Form.Item label="引用类型">
+          <Radio.Group
+            value={kind}
+            onChange={(e) => setKind(e.target.value)}
+            options={[
+              { label: '分支', value: 'branch' },
+              { label: '标签', value: 'tag' },
+              { label: '提交', value: 'commit' },
+            ]}
+          />
+        </Form.Item>
+        <Form.Item label={REF_LABELS[kind]}>
+          <Input
+            value={refName}
+            onChange={(e) => setRefName(e.target.value)}
+            placeholder={REF_PLACEHOLDERS[kind]}
+          />
+        </Form.Item>
+        <Form.Item label="版本名称" extra="留空时根据引用自动生成">
+          <Input value={version} onChange={(e) => setVersion(e.target.value)} />
+        </Form.Item>
+      </Form>
+    </Modal>
+  );
+}
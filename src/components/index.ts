@@ -5,3 +5,11 @@ export { DeployProgressModal } from './DeployProgressModal';
 export { ConfirmModal, ConfirmDeleteModal } from './ConfirmModal';
 export { ErrorBoundary } from './ErrorBoundary';
 export { TitleBar } from './TitleBar';
+export { InterpreterPickerModal } from './InterpreterPickerModal';
+export { OfflineKitExportModal } from './OfflineKitExportModal';
+export { RefInstallModal } from './RefInstallModal';
+export { ChangelogModal } from './ChangelogModal';
+export { GcPreviewModal } from './GcPreviewModal';
+export { DiskUsagePanel } from './DiskUsagePanel';
+export { DownloadProgressBar } from './DownloadProgressBar';
+export { MirrorListField, parseMirrorList } from './MirrorListField';
@@ -1,3 +1,5 @@
+import { formatBytes } from '../utils/format';
+
 export const ErrorCode = {
   INSTANCE_NOT_FOUND: 1001,
   INSTANCE_RUNNING: 1002,
@@ -7,6 +9,9 @@ export const ErrorCode = {
   CONFIG: 2001,
   IO: 2002,
   NETWORK: 2003,
+  CHECKSUM_MISMATCH: 2004,
+  DOWNLOAD_CANCELLED: 2005,
+  INSUFFICIENT_DISK_SPACE: 2006,
   PYTHON: 3001,
   PYTHON_NOT_INSTALLED: 3002,
   PROCESS: 3003,
@@ -14,6 +19,7 @@ export const ErrorCode = {
   STARTUP_TIMEOUT: 3005,
   BACKUP: 4001,
   GITHUB: 4002,
+  RATE_LIMITED: 4003,
   OTHER: 9999,
 } as const;
 
@@ -30,6 +36,10 @@ const ERROR_TEMPLATES: Record<number, ErrorTemplate> = {
   [ErrorCode.IO]: '文件系统错误: {detail}',
   [ErrorCode.NETWORK]: (p) =>
     p.url ? `无法连接到 ${p.url}: ${p.detail}` : `网络错误: ${p.detail}`,
+  [ErrorCode.CHECKSUM_MISMATCH]: '文件 {file} 校验失败，可能已损坏或被篡改 (期望 {expected}，实际 {actual})',
+  [ErrorCode.DOWNLOAD_CANCELLED]: '下载已取消',
+  [ErrorCode.INSUFFICIENT_DISK_SPACE]: (p) =>
+    `磁盘空间不足: ${p.path} 需要 ${formatBytes(Number(p.required))}，仅剩 ${formatBytes(Number(p.available))}`,
   [ErrorCode.PYTHON]: 'Python 错误: {detail}',
   [ErrorCode.PYTHON_NOT_INSTALLED]: 'Python 未安装',
   [ErrorCode.PROCESS]: '进程错误: {detail}',
@@ -40,6 +50,10 @@ const ERROR_TEMPLATES: Record<number, ErrorTemplate> = {
       ? `备份架构 (${p.backup_arch}) 与当前架构 (${p.current_arch}) 不兼容`
       : `备份错误: ${p.detail}`,
   [ErrorCode.GITHUB]: 'GitHub API 错误: {detail}',
+  [ErrorCode.RATE_LIMITED]: (p) =>
+    p.reset_at
+      ? `GitHub API 请求次数已达上限，将于 ${new Date(p.reset_at).toLocaleString()} 重置，可配置访问令牌提高上限`
+      : 'GitHub API 请求次数已达上限，请稍后再试，可配置访问令牌提高上限',
   [ErrorCode.OTHER]: '操作失败: {detail}',
 };
 
@@ -8,6 +8,7 @@ export const DEPLOY_STEPS: StepItem[] = [
   { key: 'extract', title: '解压文件' },
   { key: 'venv', title: '创建虚拟环境' },
   { key: 'deps', title: '安装依赖' },
+  { key: 'plugin_deps', title: '安装插件依赖' },
   { key: 'start', title: '启动实例' },
 ];
 
@@ -17,6 +18,7 @@ export const UPGRADE_STEPS: StepItem[] = [
   { key: 'venv', title: '创建虚拟环境' },
   { key: 'deps', title: '安装依赖' },
   { key: 'restore', title: '还原数据' },
+  { key: 'plugin_deps', title: '安装插件依赖' },
 ];
 
 export const DOWNGRADE_STEPS: StepItem[] = UPGRADE_STEPS;
@@ -1,18 +1,35 @@
 export const OPERATION_KEYS = {
   instance: (instanceId: string) => `instance:${instanceId}`,
   deleteInstance: 'delete',
+  checkInstanceUpdates: 'check-instance-updates',
 
   installVersion: (tag: string) => `install:${tag}`,
   uninstallVersion: (version: string) => `uninstall:${version}`,
+  importVersion: 'import-version',
+  installVersionFromRef: 'install-version-ref',
+  updateRefVersion: (version: string) => `update-ref:${version}`,
+  exportOfflineKit: 'export-offline-kit',
+  importOfflineKit: 'import-offline-kit',
   installComponent: (componentId: string) => `install-component:${componentId}`,
   reinstallComponent: (componentId: string) => `reinstall-component:${componentId}`,
+  installComponentFromFile: (componentId: string) => `install-component-file:${componentId}`,
+  componentProvider: (componentId: string) => `component-provider:${componentId}`,
+  checkComponentUpdates: 'check-component-updates',
+  updateComponent: (componentId: string) => `update-component:${componentId}`,
 
   backupCreate: 'backup:create',
   backupRestore: 'backup:restore',
   backupDelete: 'backup:delete',
 
-  advancedSaveGithubProxy: 'adv:save-github-proxy',
-  advancedSavePypiMirror: 'adv:save-pypi-mirror',
+  advancedSaveGithubProxies: 'adv:save-github-proxies',
+  advancedSaveGithubToken: 'adv:save-github-token',
+  advancedSavePypiMirrors: 'adv:save-pypi-mirrors',
+  advancedSaveSourceRepos: 'adv:save-source-repos',
+  advancedSaveProxy: 'adv:save-proxy',
+  advancedSaveRetention: 'adv:save-retention',
+  gcPreview: 'gc:preview',
+  gcRun: 'gc:run',
+  moveDataRoot: 'adv:move-data-root',
   advancedClearData: (instanceId: string) => `adv:data-${instanceId}`,
   advancedClearVenv: (instanceId: string) => `adv:venv-${instanceId}`,
   advancedClearPycache: (instanceId: string) => `adv:pycache-${instanceId}`,
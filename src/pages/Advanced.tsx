@@ -1,21 +1,55 @@
 import { useState, useEffect } from 'react';
-import { Typography, Input, Button, Card, Space, Form, Select, Alert, Switch } from 'antd';
-import { ReloadOutlined, PlayCircleOutlined, SaveOutlined } from '@ant-design/icons';
+import {
+  Typography,
+  Input,
+  InputNumber,
+  Button,
+  Card,
+  Space,
+  Form,
+  Select,
+  Alert,
+  Switch,
+} from 'antd';
+import {
+  ReloadOutlined,
+  PlayCircleOutlined,
+  SaveOutlined,
+  ClearOutlined,
+  FolderOpenOutlined,
+} from '@ant-design/icons';
 import { enable, disable, isEnabled } from '@tauri-apps/plugin-autostart';
+import { open } from '@tauri-apps/plugin-dialog';
 import { api } from '../api';
 import { message } from '../antdStatic';
 import { useAppStore } from '../stores';
-import { ConfirmModal } from '../components';
-import { handleApiError } from '../utils';
+import {
+  ConfirmModal,
+  DiskUsagePanel,
+  GcPreviewModal,
+  MirrorListField,
+  parseMirrorList,
+} from '../components';
+import { formatBytes, handleApiError } from '../utils';
 import { OPERATION_KEYS } from '../constants';
+import type { DataRoot, DataRootSource, GcPlan, RetentionPolicy } from '../types';
 
 const { Title, Text } = Typography;
 
-type ConfirmModalType = 'clearData' | 'clearVenv' | 'clearPycache' | null;
+type ConfirmModalType = 'clearData' | 'clearVenv' | 'clearPycache' | 'moveDataRoot' | null;
+
+const DATA_ROOT_SOURCE_LABELS: Record<DataRootSource, string> = {
+  flag: '由 --data-dir 启动参数指定',
+  env: '由环境变量 ASTRBOT_LAUNCHER_DATA_DIR 指定',
+  portable: '便携模式，位于启动器所在目录',
+  pointer: '已迁移',
+  default: '默认位置',
+};
 
 export default function Advanced() {
   const instances = useAppStore((s) => s.instances);
   const config = useAppStore((s) => s.config);
+  const diskUsage = useAppStore((s) => s.diskUsage);
   const loading = useAppStore((s) => s.loading);
   const reloadSnapshot = useAppStore((s) => s.reloadSnapshot);
   const rebuildSnapshotFromDisk = useAppStore((s) => s.rebuildSnapshotFromDisk);
@@ -24,10 +58,32 @@ export default function Advanced() {
   const finishOperation = useAppStore((s) => s.finishOperation);
 
   // Source settings
-  const [githubProxy, setGithubProxy] = useState('');
-  const [pypiMirror, setPypiMirror] = useState('');
-  const githubSaving = operations[OPERATION_KEYS.advancedSaveGithubProxy] || false;
-  const pypiSaving = operations[OPERATION_KEYS.advancedSavePypiMirror] || false;
+  const [githubProxies, setGithubProxies] = useState('');
+  const [pypiMirrors, setPypiMirrors] = useState('');
+  const githubSaving = operations[OPERATION_KEYS.advancedSaveGithubProxies] || false;
+  const pypiSaving = operations[OPERATION_KEYS.advancedSavePypiMirrors] || false;
+  const [githubToken, setGithubToken] = useState('');
+  const tokenSaving = operations[OPERATION_KEYS.advancedSaveGithubToken] || false;
+  const [sourceRepos, setSourceRepos] = useState('');
+  const sourceReposSaving = operations[OPERATION_KEYS.advancedSaveSourceRepos] || false;
+  const [proxyUrl, setProxyUrl] = useState('');
+  const [proxyUsername, setProxyUsername] = useState('');
+  const [proxyPassword, setProxyPassword] = useState('');
+  const [noProxy, setNoProxy] = useState('');
+  const proxySaving = operations[OPERATION_KEYS.advancedSaveProxy] || false;
+  const instanceUpdateChecking = operations[OPERATION_KEYS.checkInstanceUpdates] || false;
+  const [retention, setRetention] = useState<RetentionPolicy>({
+    keep_latest_versions: 3,
+    max_version_age_days: 0,
+    auto_backup_max_age_days: 7,
+    clear_pip_cache: true,
+  });
+  const retentionSaving = operations[OPERATION_KEYS.advancedSaveRetention] || false;
+  const [gcPlan, setGcPlan] = useState<GcPlan | null>(null);
+  const gcPreviewing = operations[OPERATION_KEYS.gcPreview] || false;
+  const gcRunning = operations[OPERATION_KEYS.gcRun] || false;
+  const [dataRoot, setDataRoot] = useState<DataRoot | null>(null);
+  const [dataRootTarget, setDataRootTarget] = useState<string | null>(null);
   const [initialized, setInitialized] = useState(false);
 
   // Selected values
@@ -47,10 +103,19 @@ export default function Advanced() {
       .catch(() => {});
   }, []);
 
+  useEffect(() => {
+    api.getDataRoot().then(setDataRoot).catch(handleApiError);
+  }, []);
+
   useEffect(() => {
     if (config && !initialized) {
-      setGithubProxy(config.github_proxy);
-      setPypiMirror(config.pypi_mirror);
+      setGithubProxies(config.github_proxies.join('\n'));
+      setPypiMirrors(config.pypi_mirrors.join('\n'));
+      setSourceRepos(config.source_repositories.join('\n'));
+      setProxyUrl(config.proxy.url);
+      setProxyUsername(config.proxy.username);
+      setNoProxy(config.proxy.no_proxy.join('\n'));
+      setRetention(config.retention);
       setInitialized(true);
     }
   }, [config, initialized]);
@@ -75,6 +140,31 @@ export default function Advanced() {
     }
   };
 
+  const handleAutoUpdateHourChange = async (hour: number) => {
+    try {
+      await api.saveAutoUpdateHour(hour);
+      await reloadSnapshot({ throwOnError: true });
+      message.success('设置已保存');
+    } catch (error) {
+      handleApiError(error);
+    }
+  };
+
+  const handleCheckInstanceUpdates = async () => {
+    const key = OPERATION_KEYS.checkInstanceUpdates;
+    startOperation(key);
+    try {
+      const updates = await api.checkInstanceUpdates();
+      await reloadSnapshot({ throwOnError: true });
+      const count = Object.keys(updates).length;
+      message.success(count > 0 ? `${count} 个实例有可用更新` : '所有实例均为最新版本');
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
   const handlePersistInstanceStateChange = async (checked: boolean) => {
     try {
       await api.savePersistInstanceState(checked);
@@ -99,12 +189,12 @@ export default function Advanced() {
     }
   };
 
-  const handleSaveGithubProxy = async () => {
-    const key = OPERATION_KEYS.advancedSaveGithubProxy;
+  const handleSaveGithubProxies = async () => {
+    const key = OPERATION_KEYS.advancedSaveGithubProxies;
     startOperation(key);
     try {
       await reloadSnapshot();
-      await api.saveGithubProxy(githubProxy);
+      await api.saveGithubProxies(parseMirrorList(githubProxies));
       await reloadSnapshot({ throwOnError: true });
       message.success('GitHub 代理已保存');
     } catch (error) {
@@ -114,12 +204,41 @@ export default function Advanced() {
     }
   };
 
-  const handleSavePypiMirror = async () => {
-    const key = OPERATION_KEYS.advancedSavePypiMirror;
+  const handleSaveGithubToken = async () => {
+    const key = OPERATION_KEYS.advancedSaveGithubToken;
+    startOperation(key);
+    try {
+      await api.saveGithubToken(githubToken);
+      setGithubToken('');
+      await reloadSnapshot({ throwOnError: true });
+      message.success('GitHub 访问令牌已保存');
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
+  const handleSaveSourceRepos = async () => {
+    const key = OPERATION_KEYS.advancedSaveSourceRepos;
+    startOperation(key);
+    try {
+      await api.saveSourceRepositories(parseMirrorList(sourceRepos));
+      await reloadSnapshot({ throwOnError: true });
+      message.success('源码仓库已保存');
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
+  const handleSavePypiMirrors = async () => {
+    const key = OPERATION_KEYS.advancedSavePypiMirrors;
     startOperation(key);
     try {
       await reloadSnapshot();
-      await api.savePypiMirror(pypiMirror);
+      await api.savePypiMirrors(parseMirrorList(pypiMirrors));
       await reloadSnapshot({ throwOnError: true });
       message.success('PyPI 镜像源已保存');
     } catch (error) {
@@ -129,6 +248,114 @@ export default function Advanced() {
     }
   };
 
+  const handleSaveProxy = async () => {
+    const key = OPERATION_KEYS.advancedSaveProxy;
+    startOperation(key);
+    try {
+      // Without a username the password is unused, so it is cleared
+      const keepPassword = proxyPassword === '' && proxyUsername.trim() !== '';
+      await api.saveProxyConfig(
+        {
+          url: proxyUrl,
+          username: proxyUsername,
+          no_proxy: noProxy
+            .split(/[\n,]/)
+            .map((entry) => entry.trim())
+            .filter((entry) => entry.length > 0),
+        },
+        keepPassword ? null : proxyPassword
+      );
+      setProxyPassword('');
+      await reloadSnapshot({ throwOnError: true });
+      message.success('网络代理已保存');
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
+  const handleRefreshDiskUsage = async () => {
+    try {
+      await api.refreshDiskUsage();
+    } catch (error) {
+      handleApiError(error);
+    }
+  };
+
+  const handleSaveRetention = async () => {
+    const key = OPERATION_KEYS.advancedSaveRetention;
+    startOperation(key);
+    try {
+      await api.saveRetentionPolicy(retention);
+      await reloadSnapshot({ throwOnError: true });
+      message.success('保留策略已保存');
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
+  const handlePreviewGc = async () => {
+    const key = OPERATION_KEYS.gcPreview;
+    startOperation(key);
+    try {
+      setGcPlan(await api.previewGc());
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
+  const handleRunGc = async () => {
+    if (!gcPlan) return;
+
+    const key = OPERATION_KEYS.gcRun;
+    startOperation(key);
+    try {
+      const report = await api.runGc(gcPlan.items.map((item) => item.path));
+      setGcPlan(null);
+      await reloadSnapshot({ throwOnError: true });
+      if (report.failed.length > 0) {
+        message.warning(
+          `已释放 ${formatBytes(report.reclaimed)}，${report.failed.length} 项未能删除: ${report.failed.join('; ')}`
+        );
+      } else {
+        message.success(`已释放 ${formatBytes(report.reclaimed)}`);
+      }
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
+  const handleBrowseDataRoot = async () => {
+    const path = await open({ directory: true, multiple: false });
+    if (path) {
+      setDataRootTarget(path);
+      setConfirmModal('moveDataRoot');
+    }
+  };
+
+  const handleMoveDataRoot = async () => {
+    if (!dataRootTarget) return;
+
+    const key = OPERATION_KEYS.moveDataRoot;
+    startOperation(key);
+    try {
+      // The launcher restarts into the new directory once the move succeeds.
+      await api.moveDataRoot(dataRootTarget);
+    } catch (error) {
+      handleApiError(error);
+      setConfirmModal(null);
+    } finally {
+      finishOperation(key);
+    }
+  };
+
   // Actions
   const handleClearData = async () => {
     if (!selectedDataInstance) return;
@@ -244,6 +471,8 @@ export default function Advanced() {
         return selectedPycacheInstance
           ? operations[OPERATION_KEYS.advancedClearPycache(selectedPycacheInstance)]
           : false;
+      case 'moveDataRoot':
+        return operations[OPERATION_KEYS.moveDataRoot] || false;
       default:
         return false;
     }
@@ -272,6 +501,13 @@ export default function Advanced() {
           onOk: handleClearPycache,
           isDanger: false,
         };
+      case 'moveDataRoot':
+        return {
+          title: '迁移数据目录',
+          content: `确定将全部数据迁移到 ${dataRootTarget}？迁移完成后启动器将自动重启，各实例的虚拟环境会在下次启动时重建。`,
+          onOk: handleMoveDataRoot,
+          isDanger: false,
+        };
       default:
         return null;
     }
@@ -364,6 +600,28 @@ export default function Advanced() {
               onChange={handleCheckInstanceUpdateChange}
             />
           </Form.Item>
+          <Form.Item label="自动更新时间" extra="开启自动更新的实例将在此时段内更新到最新版本">
+            <Space>
+              <Select
+                value={config?.auto_update_hour ?? 4}
+                onChange={handleAutoUpdateHourChange}
+                options={Array.from({ length: 24 }, (_, hour) => ({
+                  label: `${String(hour).padStart(2, '0')}:00`,
+                  value: hour,
+                }))}
+                disabled={!(config?.check_instance_update ?? true)}
+                style={{ width: 120 }}
+              />
+              <Button
+                icon={<ReloadOutlined />}
+                loading={instanceUpdateChecking}
+                disabled={!(config?.check_instance_update ?? true)}
+                onClick={handleCheckInstanceUpdates}
+              >
+                立即检查
+              </Button>
+            </Space>
+          </Form.Item>
           <Form.Item
             label="退出时保留实例运行状态"
             extra="启用后关闭应用时记录运行中的实例，下次启动时自动恢复"
@@ -382,31 +640,180 @@ export default function Advanced() {
       {/* Source Settings */}
       <Card title="源" size="small" style={{ marginBottom: 16 }}>
         <Form layout="vertical">
-          <Form.Item label="GitHub 代理" extra="用于加速 GitHub API 和文件下载，留空使用官方地址">
+          <Form.Item
+            label="GitHub 代理"
+            extra="每行一个，按顺序尝试，不可用时自动切换到下一个，最后回退到官方地址"
+          >
+            <MirrorListField
+              kind="github"
+              value={githubProxies}
+              onChange={setGithubProxies}
+              onSave={handleSaveGithubProxies}
+              saving={githubSaving}
+              placeholder="例如: https://cdn.gh-proxy.org"
+            />
+          </Form.Item>
+          <Form.Item
+            label="GitHub 访问令牌"
+            extra="可选，提高 GitHub API 请求上限。仅在直连 GitHub 时发送，不会经过代理"
+          >
             <Space.Compact style={{ width: '100%' }}>
-              <Input
-                value={githubProxy}
-                onChange={(e) => setGithubProxy(e.target.value)}
-                placeholder="例如: https://cdn.gh-proxy.org"
+              <Input.Password
+                value={githubToken}
+                onChange={(e) => setGithubToken(e.target.value)}
+                placeholder={
+                  config?.has_github_token ? '已设置，留空保存将清除令牌' : 'github_pat_...'
+                }
+              />
+              <Button icon={<SaveOutlined />} loading={tokenSaving} onClick={handleSaveGithubToken}>
+                保存
+              </Button>
+            </Space.Compact>
+          </Form.Item>
+          <Form.Item
+            label="源码仓库"
+            extra="每行一个 owner/repo，可在版本管理中从这些仓库的标签、分支或提交安装"
+          >
+            <Space orientation="vertical" style={{ width: '100%' }}>
+              <Input.TextArea
+                value={sourceRepos}
+                onChange={(e) => setSourceRepos(e.target.value)}
+                placeholder="例如: yourname/AstrBot"
+                autoSize={{ minRows: 2, maxRows: 6 }}
               />
               <Button
                 icon={<SaveOutlined />}
-                loading={githubSaving}
-                onClick={handleSaveGithubProxy}
+                loading={sourceReposSaving}
+                onClick={handleSaveSourceRepos}
               >
                 保存
               </Button>
-            </Space.Compact>
+            </Space>
           </Form.Item>
-          <Form.Item label="PyPI 镜像源" extra="用于加速 pip 依赖安装，留空使用官方源">
-            <Space.Compact style={{ width: '100%' }}>
-              <Input
-                value={pypiMirror}
-                onChange={(e) => setPypiMirror(e.target.value)}
-                placeholder="例如: https://pypi.tuna.tsinghua.edu.cn/simple"
+          <Form.Item
+            label="PyPI 镜像源"
+            extra="每行一个，按顺序尝试，不可用时自动切换到下一个，最后回退到官方源"
+          >
+            <MirrorListField
+              kind="pypi"
+              value={pypiMirrors}
+              onChange={setPypiMirrors}
+              onSave={handleSavePypiMirrors}
+              saving={pypiSaving}
+              placeholder="例如: https://pypi.tuna.tsinghua.edu.cn/simple"
+            />
+          </Form.Item>
+        </Form>
+      </Card>
+
+      {/* Proxy Settings */}
+      <Card title="网络代理" size="small" style={{ marginBottom: 16 }}>
+        <Form layout="vertical">
+          <Form.Item
+            label="代理地址"
+            extra="支持 http、https、socks5 和 socks5h，留空使用系统代理设置。同时用于 pip 安装依赖"
+          >
+            <Input
+              value={proxyUrl}
+              onChange={(e) => setProxyUrl(e.target.value)}
+              placeholder="例如: http://127.0.0.1:7890"
+            />
+          </Form.Item>
+          <Space style={{ width: '100%' }} align="start">
+            <Form.Item label="用户名">
+              <Input value={proxyUsername} onChange={(e) => setProxyUsername(e.target.value)} />
+            </Form.Item>
+            <Form.Item label="密码">
+              <Input.Password
+                value={proxyPassword}
+                onChange={(e) => setProxyPassword(e.target.value)}
+                placeholder={config?.has_proxy_password ? '已设置，留空保持不变' : undefined}
               />
-              <Button icon={<SaveOutlined />} loading={pypiSaving} onClick={handleSavePypiMirror}>
-                保存
+            </Form.Item>
+          </Space>
+          <Form.Item label="不使用代理的地址" extra="每行一个主机、域名或 IP 段，本机地址始终直连">
+            <Input.TextArea
+              value={noProxy}
+              onChange={(e) => setNoProxy(e.target.value)}
+              placeholder="例如: .example.com"
+              autoSize={{ minRows: 2, maxRows: 6 }}
+            />
+          </Form.Item>
+          <Button icon={<SaveOutlined />} loading={proxySaving} onClick={handleSaveProxy}>
+            保存
+          </Button>
+        </Form>
+      </Card>
+
+      {/* Storage */}
+      <Card title="存储" size="small" style={{ marginBottom: 16 }}>
+        <DiskUsagePanel
+          usage={diskUsage}
+          instances={instances}
+          onRefresh={handleRefreshDiskUsage}
+        />
+        <Form layout="vertical" style={{ marginTop: 16 }}>
+          <Space style={{ width: '100%' }} align="start" wrap>
+            <Form.Item label="保留最近安装的版本数" extra="填 0 不按数量保留">
+              <InputNumber
+                min={0}
+                value={retention.keep_latest_versions}
+                onChange={(value) =>
+                  setRetention({ ...retention, keep_latest_versions: value ?? 0 })
+                }
+              />
+            </Form.Item>
+            <Form.Item label="保留近期安装的版本（天）" extra="填 0 不按时间保留">
+              <InputNumber
+                min={0}
+                value={retention.max_version_age_days}
+                onChange={(value) =>
+                  setRetention({ ...retention, max_version_age_days: value ?? 0 })
+                }
+              />
+            </Form.Item>
+            <Form.Item label="自动备份保留天数" extra="填 0 不清理自动备份">
+              <InputNumber
+                min={0}
+                value={retention.auto_backup_max_age_days}
+                onChange={(value) =>
+                  setRetention({ ...retention, auto_backup_max_age_days: value ?? 0 })
+                }
+              />
+            </Form.Item>
+            <Form.Item label="清理 pip 缓存" extra="安装依赖期间不会清理">
+              <Switch
+                checked={retention.clear_pip_cache}
+                onChange={(checked) => setRetention({ ...retention, clear_pip_cache: checked })}
+              />
+            </Form.Item>
+          </Space>
+          <Space>
+            <Button icon={<SaveOutlined />} loading={retentionSaving} onClick={handleSaveRetention}>
+              保存
+            </Button>
+            <Button icon={<ClearOutlined />} loading={gcPreviewing} onClick={handlePreviewGc}>
+              预览清理
+            </Button>
+          </Space>
+        </Form>
+        <Text type="secondary" style={{ display: 'block', marginTop: 12 }}>
+          实例正在使用的版本始终保留。清理还会删除未安装版本的离线依赖包和残留的下载文件
+        </Text>
+        <Form layout="vertical" style={{ marginTop: 16 }}>
+          <Form.Item
+            label="数据目录"
+            style={{ marginBottom: 0 }}
+            extra={dataRoot && DATA_ROOT_SOURCE_LABELS[dataRoot.source]}
+          >
+            <Space.Compact style={{ width: '100%' }}>
+              <Input value={dataRoot?.path} readOnly />
+              <Button
+                icon={<FolderOpenOutlined />}
+                disabled={!dataRoot || ['flag', 'env', 'portable'].includes(dataRoot.source)}
+                onClick={handleBrowseDataRoot}
+              >
+                迁移...
               </Button>
             </Space.Compact>
           </Form.Item>
@@ -459,6 +866,14 @@ export default function Advanced() {
         <Text type="secondary">清空虚拟环境后，下次启动实例时会自动重新创建并安装依赖</Text>
       </Card>
 
+      <GcPreviewModal
+        open={gcPlan !== null}
+        plan={gcPlan}
+        loading={gcRunning}
+        onConfirm={handleRunGc}
+        onCancel={() => setGcPlan(null)}
+      />
+
       {/* Confirm Modal */}
       <ConfirmModal
         open={confirmModal !== null}
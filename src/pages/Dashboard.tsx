@@ -9,15 +9,18 @@ import {
   Input,
   InputNumber,
   Select,
+  Radio,
+  Switch,
   Alert,
   Tag,
   Tooltip,
   Typography,
 } from 'antd';
-import { PlusOutlined, ReloadOutlined } from '@ant-design/icons';
+import { PlusOutlined, ReloadOutlined, FolderOpenOutlined } from '@ant-design/icons';
+import { open } from '@tauri-apps/plugin-dialog';
 import { api } from '../api';
 import { message } from '../antdStatic';
-import type { InstanceStatus, GitHubRelease } from '../types';
+import type { InstanceStatus, UpdateChannel } from '../types';
 import { useInstanceUpgrade } from '../hooks';
 import { useAppStore } from '../stores';
 import {
@@ -25,19 +28,20 @@ import {
   InstanceActions,
   DeployProgressModal,
   ConfirmModal,
+  ChangelogModal,
 } from '../components';
 import { handleApiError } from '../utils';
-import { isPythonAvailableForVersion, requiredPythonComponent } from '../utils/components';
+import { isPythonAvailableForInstance } from '../utils/components';
 import { STATUS_MESSAGES, OPERATION_KEYS } from '../constants';
 
-const { Title } = Typography;
+const { Title, Link } = Typography;
 
 export default function Dashboard() {
   const navigate = useNavigate();
 
   const instances = useAppStore((s) => s.instances);
   const versions = useAppStore((s) => s.versions);
-  const config = useAppStore((s) => s.config);
+  const components = useAppStore((s) => s.components);
   const loading = useAppStore((s) => s.loading);
   const initialized = useAppStore((s) => s.initialized);
   const reloadSnapshot = useAppStore((s) => s.reloadSnapshot);
@@ -63,10 +67,15 @@ export default function Dashboard() {
   const [deleteOpen, setDeleteOpen] = useState(false);
   const [editingInstance, setEditingInstance] = useState<InstanceStatus | null>(null);
   const [instanceToDelete, setInstanceToDelete] = useState<InstanceStatus | null>(null);
+  const [changelogTarget, setChangelogTarget] = useState<{
+    instanceId: string;
+    target: string;
+  } | null>(null);
 
   // Forms
   const [createForm] = Form.useForm();
   const [editForm] = Form.useForm();
+  const createMode = Form.useWatch('mode', createForm);
 
   // Version upgrade hook
   const { upgradeInstance } = useInstanceUpgrade();
@@ -82,41 +91,34 @@ export default function Dashboard() {
     }
   }, [editFormVersion, editingInstance]);
 
-  // Version update hints
-  const [latestVersion, setLatestVersion] = useState<string | null>(null);
-  const [instanceUpdateMap, setInstanceUpdateMap] = useState<Record<string, boolean>>({});
-
-  useEffect(() => {
-    if (!config?.check_instance_update || instances.length === 0) {
-      setInstanceUpdateMap({});
-      return;
-    }
-    api
-      .fetchReleases()
-      .then(async (releases: GitHubRelease[]) => {
-        const stable = releases.find((r) => !r.prerelease);
-        if (!stable) return;
-        const latest = stable.tag_name;
-        setLatestVersion(latest);
-        const map: Record<string, boolean> = {};
-        for (const inst of instances) {
-          const cmp = await api.compareVersions(latest, inst.version);
-          map[inst.id] = cmp > 0;
-        }
-        setInstanceUpdateMap(map);
-      })
-      .catch(() => {
-        // Silently ignore fetch errors
-      });
-  }, [config?.check_instance_update, instances]);
-
   // ========================================
   // Instance Actions
   // ========================================
 
   const handleCreate = useCallback(
-    async (values: { name: string; version: string; port?: number }) => {
+    async (values: {
+      name: string;
+      mode: 'release' | 'dev';
+      version?: string;
+      source_dir?: string;
+      isolate_data?: boolean;
+      port?: number;
+    }) => {
       try {
+        if (values.mode === 'dev') {
+          await api.createDevInstance(
+            values.name,
+            values.source_dir ?? '',
+            values.port ?? 0,
+            values.isolate_data ?? false
+          );
+          await reloadSnapshot({ throwOnError: true });
+          message.success(STATUS_MESSAGES.INSTANCE_CREATED);
+          setCreateOpen(false);
+          createForm.resetFields();
+          return;
+        }
+
         await reloadSnapshot();
         const { versions: latestVersions } = useAppStore.getState();
         if (!latestVersions.some((v) => v.version === values.version)) {
@@ -124,7 +126,7 @@ export default function Dashboard() {
           return;
         }
 
-        await api.createInstance(values.name, values.version, values.port ?? 0);
+        await api.createInstance(values.name, values.version ?? '', values.port ?? 0);
         await reloadSnapshot({ throwOnError: true });
         message.success(STATUS_MESSAGES.INSTANCE_CREATED);
         setCreateOpen(false);
@@ -137,10 +139,24 @@ export default function Dashboard() {
   );
 
   const handleEdit = useCallback(
-    async (values: { name: string; version: string; port?: number }) => {
+    async (values: {
+      name: string;
+      version: string;
+      port?: number;
+      python?: string;
+      tools?: string[];
+      update_channel?: UpdateChannel;
+      auto_update?: boolean;
+    }) => {
       if (!editingInstance) return;
 
-      const isVersionChange = values.version !== editingInstance.version;
+      // Dev-mode instances run from their checkout and have no version
+      const isDev = editingInstance.source_dir !== null;
+      const isVersionChange = !isDev && values.version !== editingInstance.version;
+      const pythonOverride = values.python ?? null;
+      const tools = values.tools ?? [];
+      const updateChannel = values.update_channel ?? 'stable';
+      const autoUpdate = values.auto_update ?? false;
 
       await reloadSnapshot();
       const { instances: latestInstances, versions: latestVersions } = useAppStore.getState();
@@ -151,7 +167,7 @@ export default function Dashboard() {
         setEditFormVersion('');
         return;
       }
-      if (!latestVersions.some((v) => v.version === values.version)) {
+      if (!isDev && !latestVersions.some((v) => v.version === values.version)) {
         message.warning('所选版本不存在，请先刷新后重试');
         return;
       }
@@ -159,6 +175,40 @@ export default function Dashboard() {
       setEditOpen(false);
       setEditFormVersion('');
 
+      // Apply the Python override first so a version change deploys with it
+      if (pythonOverride !== latestInstance.python_override) {
+        try {
+          const warning = await api.setInstancePython(latestInstance.id, pythonOverride);
+          if (warning) {
+            message.warning(warning);
+          }
+        } catch (error) {
+          handleApiError(error);
+          return;
+        }
+      }
+
+      if (tools.join(',') !== latestInstance.tools.join(',')) {
+        try {
+          await api.setInstanceTools(latestInstance.id, tools);
+        } catch (error) {
+          handleApiError(error);
+          return;
+        }
+      }
+
+      if (
+        updateChannel !== latestInstance.update_channel ||
+        autoUpdate !== latestInstance.auto_update
+      ) {
+        try {
+          await api.setInstanceUpdatePolicy(latestInstance.id, updateChannel, autoUpdate);
+        } catch (error) {
+          handleApiError(error);
+          return;
+        }
+      }
+
       if (isVersionChange) {
         // Use the upgrade hook for version changes
         await upgradeInstance(latestInstance, values.name, values.version);
@@ -168,7 +218,7 @@ export default function Dashboard() {
           await api.updateInstance(
             latestInstance.id,
             values.name,
-            values.version,
+            isDev ? undefined : values.version,
             values.port ?? 0
           );
           await reloadSnapshot({ throwOnError: true });
@@ -187,10 +237,10 @@ export default function Dashboard() {
       if (!instance) return;
 
       // Check if the required Python component is installed
-      const { components } = useAppStore.getState();
-      if (!isPythonAvailableForVersion(instance.version, components)) {
-        const needed = requiredPythonComponent(instance.version);
-        const comp = components.find((c) => c.id === needed);
+      const { components: latestComponents } = useAppStore.getState();
+      if (!isPythonAvailableForInstance(instance, latestComponents)) {
+        const needed = instance.python_component;
+        const comp = latestComponents.find((c) => c.id === needed);
         message.warning(`请先在版本页面安装 ${comp?.display_name ?? needed} 组件`);
         return;
       }
@@ -325,6 +375,10 @@ export default function Dashboard() {
         name: instance.name,
         version: instance.version,
         port: instance.configured_port || 0,
+        python: instance.python_override ?? undefined,
+        tools: instance.tools,
+        update_channel: instance.update_channel,
+        auto_update: instance.auto_update,
       });
       setEditOpen(true);
     },
@@ -374,10 +428,26 @@ export default function Dashboard() {
       ellipsis: true,
       render: (version: string, record: InstanceStatus) => (
         <Space size={4}>
-          <span>{version}</span>
-          {instanceUpdateMap[record.id] && latestVersion && (
-            <Tooltip title={`最新版本: ${latestVersion}`}>
-              <Tag color="blue" style={{ marginInlineEnd: 0 }}>
+          {record.source_dir ? (
+            <Tooltip title={record.source_dir}>
+              <Tag color="purple" style={{ marginInlineEnd: 0 }}>
+                开发模式
+              </Tag>
+            </Tooltip>
+          ) : (
+            <span>{version}</span>
+          )}
+          {record.update_available && (
+            <Tooltip
+              title={`最新版本: ${record.update_available}${record.auto_update ? '（将在计划时间自动更新）' : ''}，点击查看更新日志`}
+            >
+              <Tag
+                color="blue"
+                style={{ marginInlineEnd: 0, cursor: 'pointer' }}
+                onClick={() =>
+                  setChangelogTarget({ instanceId: record.id, target: record.update_available! })
+                }
+              >
                 可更新
               </Tag>
             </Tooltip>
@@ -414,11 +484,32 @@ export default function Dashboard() {
     },
   ];
 
+  const handleBrowseSourceDir = async () => {
+    const path = await open({ directory: true, multiple: false });
+    if (path) {
+      createForm.setFieldValue('source_dir', path);
+    }
+  };
+
   const versionOptions = versions.map((v) => ({
     label: v.version,
     value: v.version,
   }));
 
+  const pythonOptions = components
+    .filter((c) => c.kind === 'python')
+    .map((c) => ({
+      label: c.installed ? c.display_name : `${c.display_name}（未安装）`,
+      value: c.id,
+    }));
+
+  const toolOptions = components
+    .filter((c) => c.kind === 'tool')
+    .map((c) => ({
+      label: c.installed ? c.display_name : `${c.display_name}（未安装）`,
+      value: c.id,
+    }));
+
   // ========================================
   // Render
   // ========================================
@@ -448,7 +539,6 @@ export default function Dashboard() {
             type="primary"
             icon={<PlusOutlined />}
             onClick={() => setCreateOpen(true)}
-            disabled={versions.length === 0}
           >
             创建实例
           </Button>
@@ -482,7 +572,12 @@ export default function Dashboard() {
         closable={false}
         destroyOnHidden
       >
-        <Form form={createForm} layout="vertical" onFinish={handleCreate}>
+        <Form
+          form={createForm}
+          layout="vertical"
+          onFinish={handleCreate}
+          initialValues={{ mode: 'release', isolate_data: true }}
+        >
           <Form.Item
             name="name"
             label="名称"
@@ -490,9 +585,45 @@ export default function Dashboard() {
           >
             <Input placeholder="我的 AstrBot" />
           </Form.Item>
-          <Form.Item name="version" label="版本" rules={[{ required: true }]}>
-            <Select options={versionOptions} placeholder="选择版本" />
+          <Form.Item name="mode" label="类型">
+            <Radio.Group
+              options={[
+                { label: '已下载的版本', value: 'release' },
+                { label: '本地源码目录（开发模式）', value: 'dev' },
+              ]}
+            />
           </Form.Item>
+          {createMode === 'dev' ? (
+            <>
+              <Form.Item
+                label="源码目录"
+                extra="直接运行该目录中的代码，启动器只创建虚拟环境并安装依赖，不会修改目录内容"
+              >
+                <Space.Compact style={{ width: '100%' }}>
+                  <Form.Item
+                    name="source_dir"
+                    noStyle
+                    rules={[{ required: true, message: '请选择源码目录' }]}
+                  >
+                    <Input placeholder="AstrBot 仓库所在目录" />
+                  </Form.Item>
+                  <Button icon={<FolderOpenOutlined />} onClick={handleBrowseSourceDir} />
+                </Space.Compact>
+              </Form.Item>
+              <Form.Item
+                name="isolate_data"
+                label="隔离数据目录"
+                valuePropName="checked"
+                extra="开启后 data 目录保存在实例目录中，而不是源码目录"
+              >
+                <Switch />
+              </Form.Item>
+            </>
+          ) : (
+            <Form.Item name="version" label="版本" rules={[{ required: true }]}>
+              <Select options={versionOptions} placeholder="选择版本" />
+            </Form.Item>
+          )}
           <Form.Item name="port" label="端口">
             <InputNumber
               min={0}
@@ -536,9 +667,35 @@ export default function Dashboard() {
           <Form.Item name="name" label="名称" rules={[{ required: true }]}>
             <Input />
           </Form.Item>
-          <Form.Item name="version" label="版本" rules={[{ required: true }]}>
-            <Select options={versionOptions} />
-          </Form.Item>
+          {editingInstance?.source_dir ? (
+            <Form.Item label="源码目录">
+              <Input value={editingInstance.source_dir} disabled />
+            </Form.Item>
+          ) : (
+            <Form.Item
+              name="version"
+              label="版本"
+              rules={[{ required: true }]}
+              extra={
+                editingInstance &&
+                editFormVersion !== editingInstance.version &&
+                editVersionCmp !== 0 && (
+                  <Link
+                    onClick={() =>
+                      setChangelogTarget({
+                        instanceId: editingInstance.id,
+                        target: editFormVersion,
+                      })
+                    }
+                  >
+                    查看版本间的更新日志
+                  </Link>
+                )
+              }
+            >
+              <Select options={versionOptions} />
+            </Form.Item>
+          )}
           <Form.Item name="port" label="端口">
             <InputNumber
               min={0}
@@ -547,9 +704,50 @@ export default function Dashboard() {
               style={{ width: '100%' }}
             />
           </Form.Item>
+          <Form.Item name="python" label="Python 运行时">
+            <Select
+              allowClear
+              placeholder="自动（按版本选择）"
+              options={pythonOptions}
+            />
+          </Form.Item>
+          <Form.Item
+            name="tools"
+            label="附加工具"
+            tooltip="启动时将所选工具加入实例的 PATH，需先在版本页面安装"
+          >
+            <Select mode="multiple" allowClear placeholder="无" options={toolOptions} />
+          </Form.Item>
+          {!editingInstance?.source_dir && (
+            <>
+              <Form.Item name="update_channel" label="更新通道">
+                <Radio.Group
+                  options={[
+                    { label: '稳定版', value: 'stable' },
+                    { label: '预发布版', value: 'prerelease' },
+                  ]}
+                />
+              </Form.Item>
+              <Form.Item
+                name="auto_update"
+                label="自动更新"
+                valuePropName="checked"
+                extra="在高级设置的计划时间自动更新到最新版本，启动失败时回滚到原版本和数据"
+              >
+                <Switch />
+              </Form.Item>
+            </>
+          )}
         </Form>
       </Modal>
 
+      <ChangelogModal
+        open={changelogTarget !== null}
+        instanceId={changelogTarget?.instanceId ?? null}
+        target={changelogTarget?.target ?? null}
+        onCancel={() => setChangelogTarget(null)}
+      />
+
       {/* Delete Modal */}
       <ConfirmModal
         open={deleteOpen}
@@ -5,14 +5,32 @@ import {
   DeleteOutlined,
   ReloadOutlined,
   InfoCircleOutlined,
+  FolderOpenOutlined,
+  DisconnectOutlined,
+  ImportOutlined,
+  BranchesOutlined,
+  SyncOutlined,
 } from '@ant-design/icons';
-import type { InstalledVersion, GitHubRelease } from '../api';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import type {
+  InstalledVersion,
+  GitHubRelease,
+  ComponentStatus,
+  ComponentUpdate,
+  RefKind,
+} from '../api';
 import { api } from '../api';
 import { message } from '../antdStatic';
 import { useReleases } from '../hooks';
 import { useVersions } from '../hooks/useVersions';
 import { useAppStore } from '../stores';
-import { ConfirmModal } from '../components';
+import {
+  ConfirmModal,
+  DownloadProgressBar,
+  InterpreterPickerModal,
+  OfflineKitExportModal,
+  RefInstallModal,
+} from '../components';
 import { OPERATION_KEYS } from '../constants';
 import { handleApiError } from '../utils';
 
@@ -26,6 +44,7 @@ export default function Versions() {
   const rebuildSnapshotFromDisk = useAppStore((s) => s.rebuildSnapshotFromDisk);
   const reloadSnapshot = useAppStore((s) => s.reloadSnapshot);
   const operations = useAppStore((s) => s.operations);
+  const downloads = useAppStore((s) => s.downloads);
   const startOperation = useAppStore((s) => s.startOperation);
   const finishOperation = useAppStore((s) => s.finishOperation);
 
@@ -33,6 +52,10 @@ export default function Versions() {
   const [detailOpen, setDetailOpen] = useState(false);
   const [uninstallOpen, setUninstallOpen] = useState(false);
   const [versionToUninstall, setVersionToUninstall] = useState<InstalledVersion | null>(null);
+  const [providerTarget, setProviderTarget] = useState<ComponentStatus | null>(null);
+  const [componentUpdates, setComponentUpdates] = useState<Record<string, ComponentUpdate>>({});
+  const [kitExportOpen, setKitExportOpen] = useState(false);
+  const [refInstallOpen, setRefInstallOpen] = useState(false);
 
   const { releases, loading: releasesLoading, fetchReleases } = useReleases();
 
@@ -56,6 +79,14 @@ export default function Versions() {
     setVersionToUninstall(null);
   };
 
+  const handleCancelDownload = useCallback(async (id: string) => {
+    try {
+      await api.cancelDownload(id);
+    } catch (error) {
+      handleApiError(error);
+    }
+  }, []);
+
   const handleInstallComponent = useCallback(
     async (componentId: string) => {
       const key = OPERATION_KEYS.installComponent(componentId);
@@ -90,6 +121,204 @@ export default function Versions() {
     [startOperation, finishOperation, reloadSnapshot]
   );
 
+  const handleInstallComponentFromFile = useCallback(
+    async (componentId: string) => {
+      const path = await open({
+        multiple: false,
+        directory: false,
+        filters: [{ name: '运行时归档', extensions: ['gz', 'zip'] }],
+      });
+      if (!path) return;
+
+      const key = OPERATION_KEYS.installComponentFromFile(componentId);
+      startOperation(key);
+      try {
+        const result = await api.installComponentFromFile(componentId, path);
+        await reloadSnapshot({ throwOnError: true });
+        message.success(result);
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [startOperation, finishOperation, reloadSnapshot]
+  );
+
+  const handleImportVersion = useCallback(async () => {
+    const path = await open({
+      multiple: false,
+      directory: false,
+      filters: [{ name: 'AstrBot 源码包', extensions: ['zip'] }],
+    });
+    if (!path) return;
+
+    const key = OPERATION_KEYS.importVersion;
+    startOperation(key);
+    try {
+      const version = await api.importVersion(path);
+      await reloadSnapshot({ throwOnError: true });
+      message.success(`已导入版本 ${version}`);
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  }, [startOperation, finishOperation, reloadSnapshot]);
+
+  const handleInstallFromRef = useCallback(
+    async (repo: string, kind: RefKind, refName: string, version?: string) => {
+      const key = OPERATION_KEYS.installVersionFromRef;
+      startOperation(key);
+      try {
+        const installed = await api.installVersionFromRef(repo, kind, refName, version);
+        await reloadSnapshot({ throwOnError: true });
+        setRefInstallOpen(false);
+        message.success(`已安装版本 ${installed}`);
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [startOperation, finishOperation, reloadSnapshot]
+  );
+
+  const handleUpdateRefVersion = useCallback(
+    async (version: string) => {
+      const key = OPERATION_KEYS.updateRefVersion(version);
+      startOperation(key);
+      try {
+        const result = await api.updateRefVersion(version);
+        await reloadSnapshot({ throwOnError: true });
+        message.success(result);
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [startOperation, finishOperation, reloadSnapshot]
+  );
+
+  const handleExportOfflineKit = useCallback(
+    async (selected: string[]) => {
+      const path = await save({
+        defaultPath: 'astrbot-offline-kit.tar.gz',
+        filters: [{ name: '离线包', extensions: ['gz'] }],
+      });
+      if (!path) return;
+
+      const key = OPERATION_KEYS.exportOfflineKit;
+      startOperation(key);
+      try {
+        const result = await api.exportOfflineKit(selected, path);
+        setKitExportOpen(false);
+        message.success(`离线包已导出到 ${result}`);
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [startOperation, finishOperation]
+  );
+
+  const handleImportOfflineKit = useCallback(async () => {
+    const path = await open({
+      multiple: false,
+      directory: false,
+      filters: [{ name: '离线包', extensions: ['gz'] }],
+    });
+    if (!path) return;
+
+    const key = OPERATION_KEYS.importOfflineKit;
+    startOperation(key);
+    try {
+      const result = await api.importOfflineKit(path);
+      await reloadSnapshot({ throwOnError: true });
+      message.success(result);
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  }, [startOperation, finishOperation, reloadSnapshot]);
+
+  const handleCheckComponentUpdates = useCallback(async () => {
+    const key = OPERATION_KEYS.checkComponentUpdates;
+    startOperation(key);
+    try {
+      const updates = await api.checkComponentUpdates();
+      setComponentUpdates(Object.fromEntries(updates.map((u) => [u.id, u])));
+      if (updates.length === 0) {
+        message.info('组件均为最新版本');
+      }
+    } catch (error) {
+      handleApiError(error);
+    } finally {
+      finishOperation(key);
+    }
+  }, [startOperation, finishOperation]);
+
+  const handleUpdateComponent = useCallback(
+    async (componentId: string) => {
+      const key = OPERATION_KEYS.updateComponent(componentId);
+      startOperation(key);
+      try {
+        const result = await api.updateComponent(componentId);
+        await reloadSnapshot({ throwOnError: true });
+        setComponentUpdates((prev) => {
+          const next = { ...prev };
+          delete next[componentId];
+          return next;
+        });
+        message.success(result);
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [startOperation, finishOperation, reloadSnapshot]
+  );
+
+  const handleRegisterProvider = useCallback(
+    async (path: string) => {
+      if (!providerTarget) return;
+      const key = OPERATION_KEYS.componentProvider(providerTarget.id);
+      startOperation(key);
+      try {
+        const interpreter = await api.registerComponentProvider(providerTarget.id, path);
+        await reloadSnapshot({ throwOnError: true });
+        message.success(`${providerTarget.display_name} 已使用 Python ${interpreter.version}`);
+        setProviderTarget(null);
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [providerTarget, startOperation, finishOperation, reloadSnapshot]
+  );
+
+  const handleUnregisterProvider = useCallback(
+    async (componentId: string) => {
+      const key = OPERATION_KEYS.componentProvider(componentId);
+      startOperation(key);
+      try {
+        await api.unregisterComponentProvider(componentId);
+        await reloadSnapshot({ throwOnError: true });
+        message.success('已改用内置运行时');
+      } catch (error) {
+        handleApiError(error);
+      } finally {
+        finishOperation(key);
+      }
+    },
+    [startOperation, finishOperation, reloadSnapshot]
+  );
+
   const isInstalled = (tagName: string) => versions.some((v) => v.version === tagName);
   const availableReleases = releases.filter((r) => !isInstalled(r.tag_name));
   const getInstalledRelease = (version: string) => releases.find((r) => r.tag_name === version);
@@ -118,7 +347,20 @@ export default function Versions() {
 
       {/* Component Management */}
       {config && (
-        <Card title="组件管理" size="small" style={{ marginBottom: 16 }}>
+        <Card
+          title="组件管理"
+          size="small"
+          style={{ marginBottom: 16 }}
+          extra={
+            <Button
+              size="small"
+              loading={operations[OPERATION_KEYS.checkComponentUpdates] || false}
+              onClick={handleCheckComponentUpdates}
+            >
+              检查更新
+            </Button>
+          }
+        >
           <List
             dataSource={components}
             renderItem={(comp) => {
@@ -126,34 +368,89 @@ export default function Versions() {
               const reinstallKey = OPERATION_KEYS.reinstallComponent(comp.id);
               const isInstalling = operations[installKey] || false;
               const isReinstalling = operations[reinstallKey] || false;
+              const isChangingProvider =
+                operations[OPERATION_KEYS.componentProvider(comp.id)] || false;
+              const update = componentUpdates[comp.id];
+              const isUpdating = operations[OPERATION_KEYS.updateComponent(comp.id)] || false;
+              const download = downloads[`component:${comp.id}`];
 
               return (
                 <List.Item
-                  actions={
-                    comp.installed
+                  actions={[
+                    ...(comp.provider
                       ? [
-                          <Tooltip title="重新安装" key="reinstall">
+                          <Tooltip title="改用内置运行时" key="unregister">
                             <Button
                               type="text"
-                              icon={<ReloadOutlined />}
-                              loading={isReinstalling}
-                              onClick={() => handleReinstallComponent(comp.id)}
+                              icon={<DisconnectOutlined />}
+                              loading={isChangingProvider}
+                              onClick={() => handleUnregisterProvider(comp.id)}
                             />
                           </Tooltip>,
                         ]
-                      : [
-                          <Button
-                            type="primary"
-                            size="small"
-                            icon={<DownloadOutlined />}
-                            loading={isInstalling}
-                            onClick={() => handleInstallComponent(comp.id)}
-                            key="install"
-                          >
-                            安装
-                          </Button>,
+                      : comp.installed
+                        ? [
+                            ...(update
+                              ? [
+                                  <Button
+                                    type="primary"
+                                    size="small"
+                                    loading={isUpdating}
+                                    onClick={() => handleUpdateComponent(comp.id)}
+                                    key="update"
+                                  >
+                                    更新到 {update.latest_version}
+                                  </Button>,
+                                ]
+                              : []),
+                            <Tooltip title="重新安装" key="reinstall">
+                              <Button
+                                type="text"
+                                icon={<ReloadOutlined />}
+                                loading={isReinstalling}
+                                onClick={() => handleReinstallComponent(comp.id)}
+                              />
+                            </Tooltip>,
+                          ]
+                        : [
+                            <Button
+                              type="primary"
+                              size="small"
+                              icon={<DownloadOutlined />}
+                              loading={isInstalling}
+                              onClick={() => handleInstallComponent(comp.id)}
+                              key="install"
+                            >
+                              安装
+                            </Button>,
+                          ]),
+                    ...(!comp.provider
+                      ? [
+                          <Tooltip title="从本地文件安装" key="install-file">
+                            <Button
+                              type="text"
+                              icon={<ImportOutlined />}
+                              loading={
+                                operations[OPERATION_KEYS.installComponentFromFile(comp.id)] ||
+                                false
+                              }
+                              onClick={() => handleInstallComponentFromFile(comp.id)}
+                            />
+                          </Tooltip>,
                         ]
-                  }
+                      : []),
+                    ...(comp.kind === 'python' && !comp.provider
+                      ? [
+                          <Tooltip title="使用已有解释器" key="provider">
+                            <Button
+                              type="text"
+                              icon={<FolderOpenOutlined />}
+                              onClick={() => setProviderTarget(comp)}
+                            />
+                          </Tooltip>,
+                        ]
+                      : []),
+                  ]}
                 >
                   <List.Item.Meta
                     title={
@@ -162,9 +459,18 @@ export default function Versions() {
                         <Tag color={comp.installed ? 'green' : undefined}>
                           {comp.installed ? '已安装' : '未安装'}
                         </Tag>
+                        {comp.installed && comp.version && <Tag>{comp.version}</Tag>}
                       </Space>
                     }
-                    description={comp.description}
+                    description={
+                      download ? (
+                        <DownloadProgressBar progress={download} onCancel={handleCancelDownload} />
+                      ) : comp.provider ? (
+                        `使用已有解释器: ${comp.provider}`
+                      ) : (
+                        comp.description
+                      )
+                    }
                   />
                 </List.Item>
               );
@@ -174,15 +480,63 @@ export default function Versions() {
       )}
 
       {/* Installed Versions */}
-      <Card title="已下载的版本" size="small" style={{ marginBottom: 16 }}>
+      <Card
+        title="已下载的版本"
+        size="small"
+        style={{ marginBottom: 16 }}
+        extra={
+          <Space>
+            <Button
+              size="small"
+              icon={<BranchesOutlined />}
+              onClick={() => setRefInstallOpen(true)}
+            >
+              从源码仓库安装
+            </Button>
+            <Button
+              size="small"
+              icon={<ImportOutlined />}
+              loading={operations[OPERATION_KEYS.importVersion] || false}
+              onClick={handleImportVersion}
+            >
+              从本地导入
+            </Button>
+            <Button
+              size="small"
+              loading={operations[OPERATION_KEYS.importOfflineKit] || false}
+              onClick={handleImportOfflineKit}
+            >
+              导入离线包
+            </Button>
+            <Button
+              size="small"
+              disabled={versions.length === 0}
+              onClick={() => setKitExportOpen(true)}
+            >
+              导出离线包
+            </Button>
+          </Space>
+        }
+      >
         <List
           dataSource={versions}
           locale={{ emptyText: '暂无已下载的版本' }}
           renderItem={(item) => {
             const release = getInstalledRelease(item.version);
+            const download = downloads[`version:${item.version}`];
             return (
               <List.Item
                 actions={[
+                  item.source?.kind === 'branch' && (
+                    <Tooltip title="更新到分支最新提交" key="update-ref">
+                      <Button
+                        type="text"
+                        icon={<SyncOutlined />}
+                        loading={operations[OPERATION_KEYS.updateRefVersion(item.version)] || false}
+                        onClick={() => handleUpdateRefVersion(item.version)}
+                      />
+                    </Tooltip>
+                  ),
                   release && (
                     <Tooltip title="详情" key="detail">
                       <Button
@@ -214,9 +568,26 @@ export default function Versions() {
                     <Space>
                       {release?.name || item.version}
                       {release?.prerelease && <Tag color="orange">预发行</Tag>}
+                      {item.source && (
+                        <Tooltip title={`${item.source.repo} @ ${item.source.commit}`}>
+                          <Tag icon={<BranchesOutlined />}>
+                            {item.source.kind === 'commit'
+                              ? item.source.commit.slice(0, 7)
+                              : `${item.source.ref_name} · ${item.source.commit.slice(0, 7)}`}
+                          </Tag>
+                        </Tooltip>
+                      )}
                     </Space>
                   }
-                  description={release ? new Date(release.published_at).toLocaleDateString() : null}
+                  description={
+                    download ? (
+                      <DownloadProgressBar progress={download} onCancel={handleCancelDownload} />
+                    ) : item.source ? (
+                      item.source.repo
+                    ) : release ? (
+                      new Date(release.published_at).toLocaleDateString()
+                    ) : null
+                  }
                 />
               </List.Item>
             );
@@ -234,6 +605,7 @@ export default function Versions() {
           }}
           renderItem={(release) => {
             const key = release.tag_name;
+            const download = downloads[`version:${key}`];
 
             return (
               <List.Item
@@ -265,7 +637,13 @@ export default function Versions() {
                       {release.prerelease && <Tag color="orange">预发行</Tag>}
                     </Space>
                   }
-                  description={new Date(release.published_at).toLocaleDateString()}
+                  description={
+                    download ? (
+                      <DownloadProgressBar progress={download} onCancel={handleCancelDownload} />
+                    ) : (
+                      new Date(release.published_at).toLocaleDateString()
+                    )
+                  }
                 />
               </List.Item>
             );
@@ -326,6 +704,34 @@ export default function Versions() {
         )}
       </Drawer>
 
+      {/* Interpreter Picker */}
+      <InterpreterPickerModal
+        component={providerTarget}
+        loading={
+          providerTarget
+            ? operations[OPERATION_KEYS.componentProvider(providerTarget.id)] || false
+            : false
+        }
+        onSelect={handleRegisterProvider}
+        onCancel={() => setProviderTarget(null)}
+      />
+
+      <OfflineKitExportModal
+        open={kitExportOpen}
+        versions={versions}
+        loading={operations[OPERATION_KEYS.exportOfflineKit] || false}
+        onExport={handleExportOfflineKit}
+        onCancel={() => setKitExportOpen(false)}
+      />
+
+      <RefInstallModal
+        open={refInstallOpen}
+        repositories={config?.source_repositories ?? []}
+        loading={operations[OPERATION_KEYS.installVersionFromRef] || false}
+        onInstall={handleInstallFromRef}
+        onCancel={() => setRefInstallOpen(false)}
+      />
+
       {/* Uninstall Modal */}
       <ConfirmModal
         open={uninstallOpen}
@@ -10,7 +10,9 @@ import type {
   BackupInfo,
   DeployProgress,
   DeployState,
+  DownloadProgress,
   ComponentStatus,
+  DiskUsage,
 } from '../types';
 import { getErrorMessage } from '../utils';
 import { MODAL_CLOSE_DELAY_MS } from '../constants';
@@ -22,6 +24,7 @@ interface AppState {
   backups: BackupInfo[];
   components: ComponentStatus[];
   config: AppConfig | null;
+  diskUsage: DiskUsage | null;
   loading: boolean;
   initialized: boolean;
 
@@ -31,6 +34,9 @@ interface AppState {
   // Deploy state
   deployState: DeployState | null;
 
+  // Active downloads, keyed by download id
+  downloads: Record<string, DownloadProgress>;
+
   // Actions
   hydrateSnapshot: (snapshot: AppSnapshot) => void;
   refresh: () => Promise<void>;
@@ -51,10 +57,12 @@ export const useAppStore = create<AppState>((set, get) => ({
   backups: [],
   components: [],
   config: null,
+  diskUsage: null,
   loading: false,
   initialized: false,
   operations: {},
   deployState: null,
+  downloads: {},
 
   hydrateSnapshot: (snapshot: AppSnapshot) => {
     set({
@@ -63,6 +71,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       backups: snapshot.backups,
       components: snapshot.components.components,
       config: snapshot.config,
+      diskUsage: snapshot.disk_usage,
       initialized: true,
     });
   },
@@ -185,7 +194,34 @@ export async function initEventListeners() {
     }
   });
 
-  unlistenFns = [unlistenSnapshot, unlistenDeploy];
+  const unlistenDownload = await listen<DownloadProgress>('download-progress', (event) => {
+    const progress = event.payload;
+    useAppStore.setState((state) => {
+      const downloads = { ...state.downloads };
+      if (
+        progress.state === 'completed' ||
+        progress.state === 'failed' ||
+        progress.state === 'cancelled'
+      ) {
+        delete downloads[progress.id];
+      } else {
+        downloads[progress.id] = progress;
+      }
+      return { downloads };
+    });
+  });
+
+  // Pick up downloads started before the window was (re)loaded
+  try {
+    const active = await api.listDownloads();
+    useAppStore.setState({
+      downloads: Object.fromEntries(active.map((progress) => [progress.id, progress])),
+    });
+  } catch (e: unknown) {
+    console.error(getErrorMessage(e));
+  }
+
+  unlistenFns = [unlistenSnapshot, unlistenDeploy, unlistenDownload];
 }
 
 export function cleanupEventListeners() {
@@ -14,20 +14,86 @@ export interface AppError {
 export interface AppConfig {
   instances: Record<string, InstanceConfig>;
   installed_versions: InstalledVersion[];
-  github_proxy: string;
-  pypi_mirror: string;
+  github_proxies: string[];
+  has_github_token: boolean; // the token itself is never sent to the webview
+  has_proxy_password: boolean;
+  pypi_mirrors: string[];
+  proxy: ProxyConfig;
+  source_repositories: string[]; // "owner/repo"
   close_to_tray: boolean;
   check_instance_update: boolean;
+  auto_update_hour: number; // local hour (0-23) for unattended instance updates
   persist_instance_state: boolean;
+  component_providers: Record<string, ComponentProvider>;
+  retention: RetentionPolicy;
+}
+
+// A rule set to 0 is disabled
+export interface RetentionPolicy {
+  keep_latest_versions: number;
+  max_version_age_days: number;
+  auto_backup_max_age_days: number;
+  clear_pip_cache: boolean;
+}
+
+export type GcItemKind =
+  | 'version'
+  | 'orphaned_archive'
+  | 'wheelhouse'
+  | 'auto_backup'
+  | 'leftover'
+  | 'pip_cache';
+
+export interface GcItem {
+  kind: GcItemKind;
+  name: string;
+  path: string;
+  size: number;
+}
+
+export interface GcPlan {
+  items: GcItem[];
+  total_size: number;
+}
+
+export interface GcReport {
+  removed: GcItem[];
+  reclaimed: number;
+  failed: string[];
+}
+
+export interface ProxyConfig {
+  url: string; // http(s):// or socks5(h)://, empty for system settings
+  username: string; // the password is never sent to the webview
+  no_proxy: string[];
+}
+
+export type MirrorKind = 'github' | 'pypi';
+
+export interface MirrorProbe {
+  url: string; // empty for the official address
+  available: boolean;
+  latency_ms: number | null;
+  error: string | null;
+}
+
+export interface ComponentProvider {
+  path: string;
+  version: string;
 }
 
 // ========================================
 // Component Types
 // ========================================
 
+export type ComponentKind = 'python' | 'tool';
+
 export interface ComponentStatus {
   id: string;
   installed: boolean;
+  version: string | null;
+  provider: string | null;
+  kind: ComponentKind;
   display_name: string;
   description: string;
 }
@@ -36,6 +102,21 @@ export interface ComponentsSnapshot {
   components: ComponentStatus[];
 }
 
+export interface ComponentUpdate {
+  id: string;
+  display_name: string;
+  current_version: string;
+  latest_version: string;
+}
+
+export interface DiscoveredInterpreter {
+  path: string;
+  version: string;
+  arch: string;
+  has_venv: boolean;
+  source: 'path' | 'pyenv' | 'prefix' | 'manual';
+}
+
 // ========================================
 // Instance Types
 // ========================================
@@ -46,14 +127,23 @@ export interface InstanceConfig {
   version: string;
   port: number;
   created_at: string;
+  python_component: string | null;
+  tools: string[];
+  source_dir: string | null; // local checkout of a dev-mode instance
+  isolate_data: boolean;
+  update_channel: UpdateChannel;
+  auto_update: boolean;
 }
 
+export type UpdateChannel = 'stable' | 'prerelease';
+
 export interface AppSnapshot {
   instances: InstanceStatus[];
   versions: InstalledVersion[];
   backups: BackupInfo[];
   components: ComponentsSnapshot;
   config: AppConfig;
+  disk_usage: DiskUsage;
 }
 
 export interface InstanceStatus {
@@ -64,6 +154,14 @@ export interface InstanceStatus {
   version: string;
   dashboard_enabled: boolean;
   configured_port: number;
+  python_component: string;
+  python_override: string | null;
+  tools: string[];
+  source_dir: string | null; // dev-mode instances have an empty version
+  isolate_data: boolean;
+  update_channel: UpdateChannel;
+  auto_update: boolean;
+  update_available: string | null; // newer release on the instance's channel
 }
 
 // ========================================
@@ -73,6 +171,19 @@ export interface InstanceStatus {
 export interface InstalledVersion {
   version: string;
   zip_path: string;
+  requires_python: string | null;
+  sha256: string | null;
+  source: VersionSource | null; // null for official releases
+  installed_at: string | null; // null for versions installed by older launchers
+}
+
+export type RefKind = 'tag' | 'branch' | 'commit';
+
+export interface VersionSource {
+  repo: string;
+  kind: RefKind;
+  ref_name: string;
+  commit: string; // full SHA resolved at install time
 }
 
 // ========================================
@@ -89,6 +200,30 @@ export interface GitHubRelease {
   body: string | null;
 }
 
+export interface Changelog {
+  from: string;
+  to: string;
+  releases: ReleaseNotes[]; // oldest first
+  has_breaking_changes: boolean;
+  complete: boolean; // false when older releases were not fetched
+}
+
+export interface ReleaseNotes {
+  tag_name: string;
+  name: string;
+  published_at: string;
+  prerelease: boolean;
+  html_url: string;
+  sections: ChangelogSection[];
+  breaking: boolean;
+}
+
+export interface ChangelogSection {
+  heading: string; // empty for text before the first heading
+  body: string;
+  breaking: boolean;
+}
+
 export interface GitHubAsset {
   name: string;
   browser_download_url: string;
@@ -122,6 +257,7 @@ export type DeployStep =
   | 'extract'
   | 'venv'
   | 'deps'
+  | 'plugin_deps'
   | 'restore'
   | 'start'
   | 'done'
@@ -132,6 +268,13 @@ export interface DeployProgress {
   step: DeployStep;
   message: string;
   progress: number; // 0-100
+  plugin_results: PluginDepsResult[];
+}
+
+export interface PluginDepsResult {
+  plugin: string;
+  success: boolean;
+  message: string;
 }
 
 export type DeployType = 'start' | 'upgrade' | 'downgrade' | null;
@@ -142,6 +285,28 @@ export interface DeployState {
   progress: DeployProgress | null;
 }
 
+// ========================================
+// Download Types
+// ========================================
+
+export type DownloadState =
+  | 'queued'
+  | 'downloading'
+  | 'retrying'
+  | 'completed'
+  | 'failed'
+  | 'cancelled';
+
+export interface DownloadProgress {
+  id: string; // e.g. "version:v4.14.8" or "component:python312"
+  url: string;
+  state: DownloadState;
+  downloaded: number;
+  total: number | null;
+  speed: number; // bytes per second
+  eta_secs: number | null;
+}
+
 // ========================================
 // UI Types
 // ========================================
@@ -157,3 +322,52 @@ export interface ConfirmModalConfig {
   onOk: () => Promise<void>;
   danger?: boolean;
 }
+
+// ========================================
+// Disk Usage Types
+// ========================================
+
+export interface EntrySize {
+  name: string;
+  size: number;
+}
+
+export interface DataUsage {
+  plugins: number;
+  logs: number;
+  temp: number;
+  knowledge_base: number;
+  other: number;
+  total: number;
+}
+
+export interface InstanceDiskUsage {
+  id: string;
+  core: number; // 0 for dev-mode instances
+  venv: number;
+  data: DataUsage;
+  total: number;
+}
+
+export interface DiskUsage {
+  instances: InstanceDiskUsage[];
+  versions: EntrySize[];
+  backups: EntrySize[];
+  components: EntrySize[];
+  wheelhouses: number;
+  cache: number;
+  total: number;
+  scanning: boolean;
+  scanned_at: string | null; // last complete scan
+}
+
+// ========================================
+// Data Root Types
+// ========================================
+
+export type DataRootSource = 'flag' | 'env' | 'portable' | 'pointer' | 'default';
+
+export interface DataRoot {
+  path: string;
+  source: DataRootSource;
+}
@@ -1 +1,2 @@
 export { getErrorMessage, handleApiError, withErrorHandling } from './error';
+export { formatBytes } from './format';